@@ -0,0 +1,54 @@
+//! Module for the history of the game's metrics.
+//!
+//! The state of the board is sampled at the end of each working day,
+//! so that it can be charted in the dashboard.
+//!
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Timestamp, TICKS_PER_DAY};
+
+/// The number of ticks between samples (one working day).
+pub const TICKS_PER_SAMPLE: Timestamp = TICKS_PER_DAY;
+
+/// The maximum number of samples kept, older samples are dropped.
+pub const MAX_SAMPLES: usize = 20 * 36;
+
+/// The state of the game at some point in time.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Sample {
+    /// the time of the sample
+    pub time: Timestamp,
+    /// the month of the sample
+    pub month: u32,
+    /// the number of tasks in each stage
+    pub backlog: u32,
+    pub candidate: u32,
+    pub progress: u32,
+    pub review: u32,
+    pub done: u32,
+    /// the number of tasks yet to be merged before a deadline
+    pub deadline_tasks: u32,
+    /// the total score (in units)
+    pub score: u32,
+    /// technical debt
+    pub complexity: u32,
+    /// the rate at which score is lost to bugs in production
+    pub linger_rate: u32,
+}
+
+impl Sample {
+    /// The number of tasks in all stages.
+    pub fn total_tasks(&self) -> u32 {
+        self.backlog + self.candidate + self.progress + self.review + self.done
+    }
+}
+
+/// Add a sample to the history, dropping the oldest samples if full.
+pub fn push_sample(samples: &mut Vec<Sample>, sample: Sample) {
+    samples.push(sample);
+    if samples.len() > MAX_SAMPLES {
+        let excess = samples.len() - MAX_SAMPLES;
+        samples.drain(..excess);
+    }
+}
@@ -0,0 +1,282 @@
+//! Module for the archive of tasks done in past months.
+//!
+//! Merged tasks only stay on the board until the month is over.
+//! Then they are summarized into one record per month,
+//! so that saved games do not keep growing with every task ever done.
+//!
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        stage::StageId,
+        task::{GameTask, TaskKind},
+    },
+    state::{Timestamp, TICKS_PER_DAY, TICKS_PER_MONTH},
+};
+
+/// A summary of the tasks merged during a month.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ArchivedMonth {
+    /// the month in which the tasks were merged
+    pub month: u32,
+    /// the number of features merged
+    pub features: u32,
+    /// the number of bug fixes merged
+    pub bug_fixes: u32,
+    /// the number of chores merged
+    pub chores: u32,
+    /// the sum of the scores of the tasks, in units
+    pub score: i32,
+    /// the number of bugs shipped along with the tasks
+    pub bugs_shipped: u32,
+    /// the sum of the lead times of the tasks, in ticks
+    pub lead_time: u64,
+    /// the sum of the cycle times of the tasks which were developed, in ticks
+    pub cycle_time: u64,
+    /// the number of tasks which were developed
+    /// (as opposed to merged straight from review)
+    pub cycled: u32,
+    /// the sum of the times under review of the tasks, in ticks
+    pub review_time: u64,
+}
+
+impl ArchivedMonth {
+    /// An empty record for the given month.
+    pub fn new(month: u32) -> Self {
+        ArchivedMonth {
+            month,
+            ..Default::default()
+        }
+    }
+
+    /// The number of tasks merged.
+    pub fn tasks(&self) -> u32 {
+        self.features + self.bug_fixes + self.chores
+    }
+
+    /// Count a merged task in this record.
+    pub fn add(&mut self, task: &GameTask, now: Timestamp) {
+        match task.kind {
+            TaskKind::Normal => self.features += 1,
+            TaskKind::Bug => self.bug_fixes += 1,
+            TaskKind::Chore => self.chores += 1,
+        }
+        self.score += task.score;
+        self.bugs_shipped += task.bugs;
+
+        let timings = task.timings(now);
+        self.lead_time += u64::from(timings.lead);
+        if let Some(cycle) = timings.cycle {
+            self.cycle_time += u64::from(cycle);
+            self.cycled += 1;
+        }
+        self.review_time += u64::from(timings.review);
+    }
+
+    /// Add up another record into this one.
+    pub fn merge(&mut self, other: &ArchivedMonth) {
+        self.features += other.features;
+        self.bug_fixes += other.bug_fixes;
+        self.chores += other.chores;
+        self.score += other.score;
+        self.bugs_shipped += other.bugs_shipped;
+        self.lead_time += other.lead_time;
+        self.cycle_time += other.cycle_time;
+        self.cycled += other.cycled;
+        self.review_time += other.review_time;
+    }
+
+    /// The average lead time in days, if any task was merged.
+    pub fn lead_days(&self) -> Option<f64> {
+        average_days(self.lead_time, self.tasks())
+    }
+
+    /// The average cycle time in days, if any task was developed.
+    pub fn cycle_days(&self) -> Option<f64> {
+        average_days(self.cycle_time, self.cycled)
+    }
+
+    /// The average time under review in days, if any task was merged.
+    pub fn review_days(&self) -> Option<f64> {
+        average_days(self.review_time, self.tasks())
+    }
+}
+
+fn average_days(total: u64, count: u32) -> Option<f64> {
+    if count == 0 {
+        None
+    } else {
+        Some(total as f64 / count as f64 / TICKS_PER_DAY as f64)
+    }
+}
+
+/// Summarize the given merged tasks into the records of the months they were merged in.
+///
+/// Tasks without a known merge time are counted in `fallback_month`.
+pub fn archive(
+    records: &mut Vec<ArchivedMonth>,
+    tasks: impl IntoIterator<Item = GameTask>,
+    now: Timestamp,
+    fallback_month: u32,
+) {
+    for task in tasks {
+        let month = task
+            .entered(StageId::Done)
+            .map(|time| (time / TICKS_PER_MONTH).min(fallback_month))
+            .unwrap_or(fallback_month);
+        let index = match records.binary_search_by_key(&month, |r| r.month) {
+            Ok(index) => index,
+            Err(index) => {
+                records.insert(index, ArchivedMonth::new(month));
+                index
+            }
+        };
+        records[index].add(&task, now);
+    }
+}
+
+/// All records added up, as if the whole game was a single month.
+pub fn lifetime(records: &[ArchivedMonth]) -> ArchivedMonth {
+    let mut total = ArchivedMonth::default();
+    for record in records {
+        total.merge(record);
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A task created at the given time
+    /// which went through the given stages, in order.
+    fn task(
+        id: u32,
+        kind: TaskKind,
+        created: Timestamp,
+        path: &[(StageId, Timestamp)],
+    ) -> GameTask {
+        let mut task = GameTask::new(id, created, "task.feature", kind, 5, 10);
+        task.bugs = 1;
+        for (stage, time) in path {
+            task.stage = *stage;
+            task.record_transition(*time);
+        }
+        task
+    }
+
+    /// A feature developed from `created + 10` and merged at `merged`.
+    fn merged_at(id: u32, created: Timestamp, merged: Timestamp) -> GameTask {
+        task(
+            id,
+            TaskKind::Normal,
+            created,
+            &[(StageId::Progress, created + 10), (StageId::Done, merged)],
+        )
+    }
+
+    fn months(records: &[ArchivedMonth]) -> Vec<u32> {
+        records.iter().map(|r| r.month).collect()
+    }
+
+    #[test]
+    fn tasks_are_summed_up_in_the_month_they_were_merged_in() {
+        let mut records = vec![];
+        archive(
+            &mut records,
+            vec![
+                merged_at(1, 1_100, 1_200),
+                merged_at(2, 100, 150),
+                task(
+                    3,
+                    TaskKind::Bug,
+                    1_300,
+                    &[(StageId::Review, 1_320), (StageId::Done, 1_400)],
+                ),
+            ],
+            1_500,
+            1,
+        );
+
+        assert_eq!(months(&records), [0, 1]);
+        let first = &records[0];
+        assert_eq!((first.features, first.bug_fixes, first.chores), (1, 0, 0));
+        assert_eq!(first.lead_time, 50);
+        assert_eq!((first.cycle_time, first.cycled), (40, 1));
+
+        let second = &records[1];
+        assert_eq!((second.features, second.bug_fixes), (1, 1));
+        assert_eq!((second.score, second.bugs_shipped), (10, 2));
+        assert_eq!(second.lead_time, 100 + 100);
+        // the bug fix went straight to review
+        assert_eq!((second.cycle_time, second.cycled), (90, 1));
+        assert_eq!(second.review_time, 80);
+
+        // later months go after, and earlier ones in between
+        archive(&mut records, vec![merged_at(4, 3_000, 3_100)], 3_500, 3);
+        archive(&mut records, vec![merged_at(5, 2_000, 2_100)], 3_500, 3);
+        archive(&mut records, vec![merged_at(6, 1_000, 1_100)], 3_500, 3);
+        assert_eq!(months(&records), [0, 1, 2, 3]);
+        assert_eq!(records[1].tasks(), 3);
+    }
+
+    #[test]
+    fn tasks_merged_after_the_month_ran_out_count_in_the_month_being_closed() {
+        let mut records = vec![ArchivedMonth::new(0)];
+        // ticks went on past the end of month 1 before it was closed
+        archive(&mut records, vec![merged_at(1, 1_500, 2_050)], 2_100, 1);
+
+        assert_eq!(months(&records), [0, 1]);
+        assert_eq!(records[1].tasks(), 1);
+        assert_eq!(records[1].lead_time, 550);
+    }
+
+    #[test]
+    fn tasks_without_a_merge_time_count_in_the_fallback_month() {
+        let mut records = vec![];
+        let task = task(1, TaskKind::Chore, 100, &[]);
+        archive(&mut records, vec![task], 1_900, 1);
+
+        assert_eq!(months(&records), [1]);
+        assert_eq!(records[0].chores, 1);
+        // still on the board until now
+        assert_eq!(records[0].lead_time, 1_800);
+        assert_eq!(records[0].cycled, 0);
+        assert_eq!(records[0].cycle_days(), None);
+    }
+
+    #[test]
+    fn the_lifetime_adds_up_every_month() {
+        let mut records = vec![];
+        archive(
+            &mut records,
+            vec![
+                merged_at(1, 100, 150),
+                merged_at(2, 1_100, 1_300),
+                task(3, TaskKind::Bug, 1_200, &[(StageId::Done, 1_250)]),
+            ],
+            1_500,
+            1,
+        );
+
+        let total = lifetime(&records);
+        assert_eq!(
+            total,
+            ArchivedMonth {
+                month: 0,
+                features: 2,
+                bug_fixes: 1,
+                chores: 0,
+                score: 15,
+                bugs_shipped: 3,
+                lead_time: 50 + 200 + 50,
+                cycle_time: 40 + 190,
+                cycled: 2,
+                review_time: 0,
+            }
+        );
+        assert_eq!(total.lead_days(), Some(100. / TICKS_PER_DAY as f64));
+        assert_eq!(lifetime(&[]), ArchivedMonth::default());
+    }
+}
@@ -0,0 +1,8 @@
+//! Entry point of the web worker which runs the simulation of the game.
+
+use gameoff2021_10x_sprint_master::simulation::Simulation;
+use yew::agent::Threaded;
+
+fn main() {
+    Simulation::register();
+}
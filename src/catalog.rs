@@ -0,0 +1,385 @@
+//! Module for the catalog of major game events.
+//!
+//! All random events which may happen over the course of the game
+//! are described in a JSON document:
+//! how likely they are, under which conditions they may happen,
+//! what they do to the world state, and which message is shown.
+//! The default catalog is bundled with the game,
+//! see `assets/data/events.json`.
+//!
+
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::UnwrapThrowExt;
+
+use crate::{components::messages::Message, i18n::tr, state::WorldState};
+
+/// The JSON content of the event catalog bundled with the game.
+pub static DEFAULT_CATALOG: &str = include_str!("../assets/data/events.json");
+
+/// A full catalog of major events.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EventCatalog {
+    /// The range of each major event roll.
+    ///
+    /// Each event takes a portion of this range equal to its weight,
+    /// whatever is left is the chance of nothing happening.
+    pub roll: u32,
+    /// All events in the catalog
+    pub events: Vec<EventSpec>,
+}
+
+/// An error which prevents an event catalog from being loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogError {
+    /// the catalog is not valid JSON or misses required fields
+    Malformed(String),
+    /// the roll of the catalog is zero
+    ZeroRoll,
+    /// the weights of all events add up to more than the roll
+    Overweight,
+    /// an event in the catalog could never be rolled into the game
+    Invalid { event: String, reason: &'static str },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Malformed(reason) => write!(f, "malformed event catalog: {}", reason),
+            CatalogError::ZeroRoll => write!(f, "the event catalog roll must be positive"),
+            CatalogError::Overweight => {
+                write!(f, "the event weights add up to more than the roll")
+            }
+            CatalogError::Invalid { event, reason } => {
+                write!(f, "invalid event `{}`: {}", event, reason)
+            }
+        }
+    }
+}
+
+impl EventCatalog {
+    /// Load an event catalog from its JSON representation.
+    ///
+    /// Fails if any value would break rolling for events,
+    /// such as a zero roll, weights adding up to more than the roll,
+    /// or a range with its bounds swapped.
+    pub fn from_json(json: &str) -> Result<Self, CatalogError> {
+        let catalog: EventCatalog =
+            serde_json::from_str(json).map_err(|e| CatalogError::Malformed(e.to_string()))?;
+        catalog.validate()?;
+        Ok(catalog)
+    }
+
+    /// Check that events can be rolled from this catalog.
+    fn validate(&self) -> Result<(), CatalogError> {
+        if self.roll == 0 {
+            return Err(CatalogError::ZeroRoll);
+        }
+        let total = self
+            .events
+            .iter()
+            .try_fold(0_u32, |acc, event| acc.checked_add(event.weight));
+        if !matches!(total, Some(total) if total <= self.roll) {
+            return Err(CatalogError::Overweight);
+        }
+        for event in &self.events {
+            event.validate().map_err(|reason| CatalogError::Invalid {
+                event: event.id.clone(),
+                reason,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Load the event catalog bundled with the game.
+    pub fn bundled() -> Self {
+        Self::from_json(DEFAULT_CATALOG).expect_throw("bundled event catalog is invalid")
+    }
+
+    /// Obtain the event which takes the given position in the roll,
+    /// if any.
+    pub fn event_at(&self, roll: u32) -> Option<&EventSpec> {
+        let mut acc: u32 = 0;
+        for event in &self.events {
+            acc = acc.saturating_add(event.weight);
+            if roll < acc {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+/// The description of a major event.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EventSpec {
+    /// a unique name for the event
+    pub id: String,
+    /// the chance of the event to happen, relative to the catalog roll
+    pub weight: u32,
+    /// the conditions for the event to happen
+    #[serde(default)]
+    pub conditions: EventConditions,
+    /// what the event does in the game
+    pub effect: EventEffect,
+    /// changes to the world state applied when the event happens
+    #[serde(default)]
+    pub changes: StateChanges,
+    /// the possible messages to show, one of them is picked at random
+    pub messages: Vec<MessageTemplate>,
+}
+
+impl EventSpec {
+    /// Check that the event can be rolled,
+    /// describing what is wrong otherwise.
+    fn validate(&self) -> Result<(), &'static str> {
+        fn range<T: PartialOrd>(range: &(T, T), reason: &'static str) -> Result<(), &'static str> {
+            if range.0 <= range.1 {
+                Ok(())
+            } else {
+                Err(reason)
+            }
+        }
+
+        if self.messages.is_empty() {
+            return Err("no messages to show");
+        }
+        match &self.effect {
+            EventEffect::BugReported { score, difficulty } => {
+                range(score, "score range is inverted")?;
+                range(difficulty, "difficulty range is inverted")?;
+            }
+            EventEffect::FeatureRequested {
+                tasks,
+                score,
+                difficulty,
+                ..
+            } => {
+                range(tasks, "task count range is inverted")?;
+                range(score, "score range is inverted")?;
+                range(difficulty, "difficulty range is inverted")?;
+            }
+            EventEffect::Report
+            | EventEffect::HumanQuit
+            | EventEffect::OutdatedDependency { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// The conditions for a major event to happen.
+///
+/// Conditions which are not specified always hold.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EventConditions {
+    /// the first month in which the event can happen
+    #[serde(default)]
+    pub min_month: Option<u32>,
+    /// the last month in which the event can happen
+    #[serde(default)]
+    pub max_month: Option<u32>,
+    /// the minimum technical debt of the product
+    #[serde(default)]
+    pub min_complexity: Option<u32>,
+    /// the maximum technical debt of the product
+    #[serde(default)]
+    pub max_complexity: Option<u32>,
+    /// the minimum number of humans in the team, including You
+    #[serde(default)]
+    pub min_team_size: Option<u32>,
+    /// the maximum number of humans in the team, including You
+    #[serde(default)]
+    pub max_team_size: Option<u32>,
+    /// the minimum number of bugs in the product
+    #[serde(default)]
+    pub min_bugs: Option<u32>,
+    /// the minimum pressure on the team
+    /// (technical debt plus 5 for each team member)
+    #[serde(default)]
+    pub min_pressure: Option<u32>,
+}
+
+impl EventConditions {
+    /// Check whether all conditions hold for the given state.
+    pub fn are_met(&self, state: &WorldState) -> bool {
+        let team_size = state.humans.team_size() as u32;
+        let pressure = state.complexity + team_size * 5;
+
+        fn at_least(min: Option<u32>, value: u32) -> bool {
+            min.map(|min| value >= min).unwrap_or(true)
+        }
+        fn at_most(max: Option<u32>, value: u32) -> bool {
+            max.map(|max| value <= max).unwrap_or(true)
+        }
+
+        at_least(self.min_month, state.month)
+            && at_most(self.max_month, state.month)
+            && at_least(self.min_complexity, state.complexity)
+            && at_most(self.max_complexity, state.complexity)
+            && at_least(self.min_team_size, team_size)
+            && at_most(self.max_team_size, team_size)
+            && at_least(self.min_bugs, state.bugs)
+            && at_least(self.min_pressure, pressure)
+    }
+}
+
+/// What a major event does in the game.
+///
+/// Ranges are written as `[min, max]`, both inclusive.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventEffect {
+    /// nothing other than showing the message
+    Report,
+    /// a bug was reported by clients, a bug task is added
+    BugReported {
+        score: (i32, i32),
+        difficulty: (u32, u32),
+    },
+    /// a random human other than You quits
+    HumanQuit,
+    /// extraordinary features were requested,
+    /// tasks with a deadline are added
+    /// (`description` is a message ID)
+    FeatureRequested {
+        description: String,
+        tasks: (u32, u32),
+        score: (i32, i32),
+        difficulty: (u32, u32),
+    },
+    /// one of the outdated dependencies is causing trouble,
+    /// adding technical debt and a chore to migrate it
+    OutdatedDependency {
+        /// the number of ticks that a dependency must be outdated for
+        /// before it can cause trouble
+        min_age: u32,
+        /// technical debt added for each major version behind
+        complexity_per_version: u32,
+        /// technical debt added for each full month outdated
+        complexity_per_month: u32,
+        /// difficulty of the migration chore for each major version behind
+        difficulty_per_version: u32,
+    },
+}
+
+/// Changes to apply to the world state.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StateChanges {
+    /// score to add, in milliparts of a unit
+    #[serde(default)]
+    pub score: i32,
+    /// technical debt to add
+    #[serde(default)]
+    pub complexity: i32,
+    /// hidden bugs to add to the product
+    #[serde(default)]
+    pub bugs: i32,
+    /// change in the rate at which You can devise new tasks
+    #[serde(default)]
+    pub task_ingest_rate: i32,
+}
+
+/// A message with template variables (e.g. `$PRODUCT_NAME`).
+///
+/// Title and body are message IDs in the locale bundles,
+/// any text which is not a known ID is shown as is.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MessageTemplate {
+    pub title: String,
+    pub body: String,
+}
+
+impl MessageTemplate {
+    /// Create the message in the current locale,
+    /// replacing the given template variables.
+    pub fn to_message(&self, vars: &HashMap<&'static str, String>) -> Message {
+        Message::new_simple_with_vars(tr(&self.title), tr(&self.body), vars)
+    }
+}
+
+/// Collect the template variables available to all event messages.
+pub fn template_vars(state: &WorldState) -> HashMap<&'static str, String> {
+    let team_size = state.humans.team_size();
+
+    let mut vars = HashMap::new();
+    vars.insert("$PRODUCT_NAME", state.product_name.to_string());
+    vars.insert("$MONTH", state.month.to_string());
+    vars.insert("$TEAM_SIZE", team_size.to_string());
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_catalog_is_valid() {
+        assert!(EventCatalog::from_json(DEFAULT_CATALOG).is_ok());
+    }
+
+    #[test]
+    fn malformed_catalogs_are_rejected() {
+        let message = r#"{"title": "t", "body": "b"}"#;
+        let catalog = |roll: u32, effect: &str, messages: &str| {
+            format!(
+                r#"{{"roll": {}, "events": [{{"id": "e", "weight": 1, "effect": {}, "messages": [{}]}}]}}"#,
+                roll, effect, messages
+            )
+        };
+        let report = r#"{"type": "report"}"#;
+
+        assert!(EventCatalog::from_json(&catalog(10, report, message)).is_ok());
+        assert!(matches!(
+            EventCatalog::from_json("{\"roll\": 10}"),
+            Err(CatalogError::Malformed(_))
+        ));
+        assert_eq!(
+            EventCatalog::from_json(&catalog(0, report, message)),
+            Err(CatalogError::ZeroRoll)
+        );
+        let weighted = |roll: u32, weights: [u32; 2]| {
+            format!(
+                r#"{{"roll": {}, "events": [
+                    {{"id": "a", "weight": {}, "effect": {}, "messages": [{}]}},
+                    {{"id": "b", "weight": {}, "effect": {}, "messages": [{}]}}]}}"#,
+                roll, weights[0], report, message, weights[1], report, message
+            )
+        };
+        assert!(EventCatalog::from_json(&weighted(10, [4, 6])).is_ok());
+        assert_eq!(
+            EventCatalog::from_json(&weighted(10, [4, 7])),
+            Err(CatalogError::Overweight)
+        );
+        assert_eq!(
+            EventCatalog::from_json(&weighted(u32::MAX, [u32::MAX, 1])),
+            Err(CatalogError::Overweight)
+        );
+        assert_eq!(
+            EventCatalog::from_json(&catalog(10, report, "")),
+            Err(CatalogError::Invalid {
+                event: "e".to_string(),
+                reason: "no messages to show"
+            })
+        );
+
+        let bug = r#"{"type": "bug_reported", "score": [5, 1], "difficulty": [1, 2]}"#;
+        assert_eq!(
+            EventCatalog::from_json(&catalog(10, bug, message)),
+            Err(CatalogError::Invalid {
+                event: "e".to_string(),
+                reason: "score range is inverted"
+            })
+        );
+
+        let feature = r#"{"type": "feature_requested", "description": "d",
+            "tasks": [3, 2], "score": [1, 2], "difficulty": [1, 2]}"#;
+        assert_eq!(
+            EventCatalog::from_json(&catalog(10, feature, message)),
+            Err(CatalogError::Invalid {
+                event: "e".to_string(),
+                reason: "task count range is inverted"
+            })
+        );
+    }
+}
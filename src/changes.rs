@@ -0,0 +1,305 @@
+//! Module for describing what changed in the world state.
+//!
+//! The tasks and humans changed by a request are recorded
+//! where they are changed, on the board and in the roster,
+//! while the counters and the log are compared before and after.
+//! This way the game component only renders again
+//! the parts of the board which changed,
+//! and the simulation only sends it those parts.
+//!
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analytics::{self, Sample},
+    archive::ArchivedMonth,
+    components::{human::GameHuman, task::GameTask},
+    dependencies::Dependency,
+    event_log::LogEntry,
+    roster::Roster,
+    state::{Timestamp, WorldState},
+};
+
+/// What changed in the world state after applying a request.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeSet {
+    /// the tasks which were added, changed or removed, by ID
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub tasks: BTreeSet<u32>,
+    /// the team members who joined, changed or left, by ID
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub humans: BTreeSet<u32>,
+    /// whether someone joined or left the team
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub team: bool,
+    /// whether any of the counters changed,
+    /// such as the time or the score
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub counters: bool,
+    /// whether anything was written to the event log
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub log: bool,
+    /// whether a sample of the game's metrics was taken
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub samples: bool,
+    /// whether tasks done were archived
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub archive: bool,
+    /// whether the product's dependencies changed
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub dependencies: bool,
+}
+
+fn is_false(x: &bool) -> bool {
+    !x
+}
+
+impl ChangeSet {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        *self == ChangeSet::default()
+    }
+
+    /// Add up the changes of another request.
+    pub fn merge(&mut self, other: ChangeSet) {
+        self.tasks.extend(other.tasks);
+        self.humans.extend(other.humans);
+        self.team |= other.team;
+        self.counters |= other.counters;
+        self.log |= other.log;
+        self.samples |= other.samples;
+        self.archive |= other.archive;
+        self.dependencies |= other.dependencies;
+    }
+}
+
+/// The counters of the world state,
+/// which are small enough to be sent whole whenever one of them changes.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Counters {
+    pub month: u32,
+    pub time: Timestamp,
+    pub time_in_month: u32,
+    pub next_task_id: u32,
+    pub total_score: u32,
+    pub score_in_month: i32,
+    pub bugs: u32,
+    pub bugs_fixed_in_total: u32,
+    pub bugs_fixed_in_month: u32,
+    pub complexity: u32,
+    pub score_linger_rate: u32,
+    pub task_ingest_rate: u32,
+    pub tutorial: Option<u32>,
+    pub tutorial_since: Timestamp,
+    pub ceo_message_delivered: bool,
+}
+
+impl Counters {
+    /// The counters of the given state.
+    pub fn of(state: &WorldState) -> Self {
+        Counters {
+            month: state.month,
+            time: state.time,
+            time_in_month: state.time_in_month,
+            next_task_id: state.next_task_id,
+            total_score: state.total_score,
+            score_in_month: state.score_in_month,
+            bugs: state.bugs,
+            bugs_fixed_in_total: state.bugs_fixed_in_total,
+            bugs_fixed_in_month: state.bugs_fixed_in_month,
+            complexity: state.complexity,
+            score_linger_rate: state.score_linger_rate,
+            task_ingest_rate: state.task_ingest_rate,
+            tutorial: state.tutorial,
+            tutorial_since: state.tutorial_since,
+            ceo_message_delivered: state.ceo_message_delivered,
+        }
+    }
+
+    /// Set the counters of the given state to these.
+    fn apply_to(self, state: &mut WorldState) {
+        state.month = self.month;
+        state.time = self.time;
+        state.time_in_month = self.time_in_month;
+        state.next_task_id = self.next_task_id;
+        state.total_score = self.total_score;
+        state.score_in_month = self.score_in_month;
+        state.bugs = self.bugs;
+        state.bugs_fixed_in_total = self.bugs_fixed_in_total;
+        state.bugs_fixed_in_month = self.bugs_fixed_in_month;
+        state.complexity = self.complexity;
+        state.score_linger_rate = self.score_linger_rate;
+        state.task_ingest_rate = self.task_ingest_rate;
+        state.tutorial = self.tutorial;
+        state.tutorial_since = self.tutorial_since;
+        state.ceo_message_delivered = self.ceo_message_delivered;
+    }
+}
+
+/// The parts of the world state which are compared for changes.
+#[derive(Debug)]
+pub struct Snapshot {
+    counters: Counters,
+    log_len: usize,
+    last_log: Option<LogEntry>,
+    last_sample: Option<Timestamp>,
+    dependencies: Vec<Dependency>,
+}
+
+impl Snapshot {
+    /// Take a snapshot of the given state.
+    pub fn of(state: &WorldState) -> Self {
+        Snapshot {
+            counters: Counters::of(state),
+            log_len: state.log.len(),
+            last_log: state.log.last().cloned(),
+            last_sample: state.samples.last().map(|s| s.time),
+            dependencies: state.dependencies.clone(),
+        }
+    }
+
+    /// What changed from this snapshot to the given state,
+    /// taking the changes recorded by its board and roster.
+    pub fn changes(&self, state: &mut WorldState) -> ChangeSet {
+        let tasks = state.tasks.take_changed();
+        let (humans, team) = state.humans.take_changed();
+
+        ChangeSet {
+            tasks,
+            humans,
+            team,
+            counters: self.counters != Counters::of(state),
+            log: self.log_len != state.log.len() || self.last_log.as_ref() != state.log.last(),
+            samples: self.last_sample != state.samples.last().map(|s| s.time),
+            // tasks are only archived as a new month begins
+            archive: self.counters.month != state.month,
+            dependencies: self.dependencies != state.dependencies,
+        }
+    }
+}
+
+/// The parts of the world state which changed after applying a request,
+/// as sent by the simulation to the game component.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatePatch {
+    /// all counters, if any of them changed
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counters: Option<Counters>,
+    /// the tasks which were added or changed
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tasks: Vec<GameTask>,
+    /// the IDs of the tasks which left the board
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_tasks: Vec<u32>,
+    /// the whole roster, if someone joined or left the team
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roster: Option<Roster>,
+    /// the humans who changed otherwise
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub humans: Vec<GameHuman>,
+    /// the whole log, which is kept short, if anything was written to it
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log: Option<Vec<LogEntry>>,
+    /// the sample of the game's metrics which was taken, if any
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample: Option<Sample>,
+    /// the whole archive, if tasks were archived
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<Vec<ArchivedMonth>>,
+    /// all dependencies, if any of them changed
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Vec<Dependency>>,
+}
+
+impl StatePatch {
+    /// The parts of the given state which changed,
+    /// as described by the given change set.
+    pub fn of(state: &WorldState, changed: &ChangeSet) -> Self {
+        let mut tasks = vec![];
+        let mut removed_tasks = vec![];
+        for id in &changed.tasks {
+            match state.tasks.get(*id) {
+                Some(task) => tasks.push(task.clone()),
+                None => removed_tasks.push(*id),
+            }
+        }
+
+        let (roster, humans) = if changed.team {
+            (Some(state.humans.clone()), vec![])
+        } else {
+            let humans = changed
+                .humans
+                .iter()
+                .filter_map(|id| state.humans.get(*id))
+                .cloned()
+                .collect();
+            (None, humans)
+        };
+
+        StatePatch {
+            counters: changed.counters.then(|| Counters::of(state)),
+            tasks,
+            removed_tasks,
+            roster,
+            humans,
+            log: changed.log.then(|| state.log.clone()),
+            sample: state.samples.last().filter(|_| changed.samples).cloned(),
+            archive: changed.archive.then(|| state.archive.clone()),
+            dependencies: changed.dependencies.then(|| state.dependencies.clone()),
+        }
+    }
+
+    /// Apply these changes to a copy of the world state.
+    pub fn apply_to(self, state: &mut WorldState) {
+        if let Some(counters) = self.counters {
+            counters.apply_to(state);
+        }
+        for task in self.tasks {
+            state.tasks.put(task);
+        }
+        for id in self.removed_tasks {
+            state.tasks.remove(id);
+        }
+        if let Some(roster) = self.roster {
+            state.humans = roster;
+        }
+        for human in self.humans {
+            state.humans.put(human);
+        }
+        if let Some(log) = self.log {
+            state.log = log;
+        }
+        if let Some(sample) = self.sample {
+            analytics::push_sample(&mut state.samples, sample);
+        }
+        if let Some(archive) = self.archive {
+            state.archive = archive;
+        }
+        if let Some(dependencies) = self.dependencies {
+            state.dependencies = dependencies;
+        }
+
+        // these changes were made elsewhere and are already known
+        state.tasks.take_changed();
+        state.humans.take_changed();
+    }
+}
@@ -3,6 +3,7 @@ use std::rc::Rc;
 use yew::prelude::*;
 
 use super::stage::Stage;
+use crate::i18n::tr_vars;
 
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct Props {
@@ -53,9 +54,9 @@ impl Component for Board {
         };
 
         html! {
-            <div class="board">
-                <h3>{&self.props.product_name}{" Workboard"}</h3>
-                <div class=alert_classes>{alert_message}</div>
+            <div class="board" role="region" aria-labelledby="board-title">
+                <h3 id="board-title">{tr_vars("board.title", &[("$PRODUCT_NAME", &self.props.product_name)])}</h3>
+                <div class=alert_classes role="alert">{alert_message}</div>
                 { for self.props.children.iter() }
             </div>
         }
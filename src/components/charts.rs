@@ -0,0 +1,162 @@
+//! Inline SVG charts for the analytics dashboard.
+
+use yew::prelude::*;
+
+use crate::analytics::Sample;
+use crate::i18n::tr;
+
+const WIDTH: f64 = 400.;
+const HEIGHT: f64 = 150.;
+
+/// The position of the sample at the given index on the horizontal axis.
+fn x_of(index: usize, count: usize) -> f64 {
+    if count <= 1 {
+        0.
+    } else {
+        index as f64 * WIDTH / (count - 1) as f64
+    }
+}
+
+/// The position of the value on the vertical axis.
+fn y_of(value: f64, max: f64) -> f64 {
+    if max <= 0. {
+        HEIGHT
+    } else {
+        HEIGHT - value * HEIGHT / max
+    }
+}
+
+/// The points of a polyline over the given values.
+fn line_points(values: &[f64], max: f64) -> String {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!("{:.1},{:.1}", x_of(i, values.len()), y_of(*v, max)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The path of the area between two lines over the given values.
+fn area_path(lower: &[f64], upper: &[f64], max: f64) -> String {
+    let count = upper.len();
+    let top = upper
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!("{:.1},{:.1}", x_of(i, count), y_of(*v, max)));
+    let bottom = lower
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, v)| format!("{:.1},{:.1}", x_of(i, count), y_of(*v, max)));
+    format!("M{}Z", top.chain(bottom).collect::<Vec<_>>().join(" L"))
+}
+
+/// The frame of a chart, with the maximum value at the top.
+fn chart(title: &str, max: f64, content: Html, legend: Html) -> Html {
+    let view_box = format!("0 0 {} {}", WIDTH, HEIGHT);
+    html! {
+        <figure class="chart">
+            <figcaption>{title}</figcaption>
+            <div class="chart-body">
+                <span class="chart-max">{max as u32}</span>
+                <svg class="chart-svg" viewBox=view_box preserveAspectRatio="none">
+                    {content}
+                </svg>
+            </div>
+            <div class="chart-legend">{legend}</div>
+        </figure>
+    }
+}
+
+fn legend_item(class: &'static str, label: String) -> Html {
+    html! {
+        <span class="chart-legend-item">
+            <span class=classes!("chart-swatch", class) />
+            {label}
+        </span>
+    }
+}
+
+/// A band of the cumulative flow chart:
+/// its CSS class, the message ID of its label
+/// and the number of tasks it stands for in each sample.
+type FlowBand = (&'static str, &'static str, fn(&Sample) -> u32);
+
+/// A chart of the number of tasks in each stage over time,
+/// stacked from Done at the bottom to Backlog at the top.
+pub fn cumulative_flow(samples: &[Sample]) -> Html {
+    let stages: [FlowBand; 5] = [
+        ("chart-done", "stage.done", |s| s.done),
+        ("chart-review", "stage.review", |s| s.review),
+        ("chart-progress", "stage.progress", |s| s.progress),
+        ("chart-candidate", "stage.candidate", |s| s.candidate),
+        ("chart-backlog", "stage.backlog", |s| s.backlog),
+    ];
+    let max = samples.iter().map(Sample::total_tasks).max().unwrap_or(0) as f64;
+
+    let mut lower = vec![0.; samples.len()];
+    let mut areas = vec![];
+    for (class, _, count) in &stages {
+        let upper: Vec<f64> = samples
+            .iter()
+            .zip(&lower)
+            .map(|(s, l)| l + count(s) as f64)
+            .collect();
+        let d = area_path(&lower, &upper, max);
+        areas.push(html! { <path class=*class d=d /> });
+        lower = upper;
+    }
+
+    let legend = stages
+        .iter()
+        .rev()
+        .map(|(class, label, _)| legend_item(class, tr(label)))
+        .collect::<Html>();
+
+    chart(
+        &tr("dashboard.flow"),
+        max,
+        areas.into_iter().collect(),
+        legend,
+    )
+}
+
+/// A chart of the tasks with a deadline left to merge in the current month.
+pub fn burndown(samples: &[Sample]) -> Html {
+    let month = samples.last().map(|s| s.month).unwrap_or(0);
+    let values: Vec<f64> = samples
+        .iter()
+        .filter(|s| s.month == month)
+        .map(|s| s.deadline_tasks as f64)
+        .collect();
+    let max = values.iter().cloned().fold(0., f64::max);
+
+    let content = html! {
+        <polyline class="chart-line chart-deadline" points=line_points(&values, max) />
+    };
+    let legend = legend_item("chart-deadline", tr("dashboard.deadline_tasks"));
+
+    chart(&tr("dashboard.burndown"), max, content, legend)
+}
+
+/// A chart of the total score and technical debt over time.
+pub fn score(samples: &[Sample]) -> Html {
+    let scores: Vec<f64> = samples.iter().map(|s| s.score as f64).collect();
+    let complexity: Vec<f64> = samples.iter().map(|s| s.complexity as f64).collect();
+    let max = scores.iter().chain(&complexity).cloned().fold(0., f64::max);
+
+    let content = html! {
+        <>
+            <polyline class="chart-line chart-score" points=line_points(&scores, max) />
+            <polyline class="chart-line chart-complexity" points=line_points(&complexity, max) />
+        </>
+    };
+    let legend = html! {
+        <>
+            {legend_item("chart-score", tr("dashboard.score"))}
+            {legend_item("chart-complexity", tr("dashboard.complexity"))}
+        </>
+    };
+
+    chart(&tr("dashboard.score_title"), max, content, legend)
+}
@@ -1,21 +1,54 @@
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
 use gloo_timers::callback::Timeout;
 use wasm_bindgen::prelude::Closure;
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use yew::prelude::*;
+use yew::services::render::{RenderService, RenderTask};
 use yew::utils::document;
+use yew::web_sys;
 
 use crate::audio::{play_endofmonth, play_zipclick};
+use crate::changes::ChangeSet;
 use crate::components::stage::StageId;
-use crate::components::task::{GameTaskBuilder, TaskKind};
 use crate::components::{
-    board::Board, clock::Clock, human::Human, modal::Modal, stage::Stage, task::Task,
+    board::Board,
+    clock::Clock,
+    human::Human,
+    modal::Modal,
+    perf::{PaintStats, PerfOverlay},
+    stage::Stage,
+    task::Task,
+    timeline::Timeline,
+    unassign::Unassign,
 };
 use crate::event_bus::{EventBus, EventBusRequest};
-use crate::services::{EventReactor, GameSpeed, GameWatch};
-use crate::state::{EventOutcome, WorldState};
+use crate::i18n::{self, tr, tr_count};
+use crate::keyboard::{self, Command};
+use crate::saves::{self, SaveStats};
+use crate::services::{ClockSettings, GameSpeed, GameWatch};
+use crate::share;
+use crate::simulation::{Json, Simulation, SimulationInput, SimulationOutput};
+use crate::state::{EventOutcome, MoveError, WorldState, TICKS_PER_DAY};
+use crate::tutorial;
 
 use super::messages::Message;
 use super::task::GameTask;
+use crate::data_transfer::payload::TaskTransfer;
+
+/// The stages of the board, from left to right.
+const STAGES: [StageId; 5] = [
+    StageId::Backlog,
+    StageId::Candidate,
+    StageId::Progress,
+    StageId::Review,
+    StageId::Done,
+];
+
+/// The number of ticks between renders of the times shown on tasks,
+/// which are presented in tenths of a day.
+const TIME_STEP: u32 = TICKS_PER_DAY / 10;
 
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct Props {
@@ -29,7 +62,11 @@ pub enum GameStateOrigin {
         project_name: String,
         onboarding: bool,
     },
-    Continue,
+    /// Continue a game loaded from the given save slot
+    Continue {
+        slot: u32,
+        state: Rc<WorldState>,
+    },
     Dummy,
 }
 
@@ -44,10 +81,14 @@ pub enum Msg {
     OpenMessage(Message),
     /// an event was received via the event bus
     Event(EventBusRequest),
-    /// an event to be triggered after some milliseconds
-    EventWithTimeout { ms: u32, event: EventBusRequest },
+    /// the simulation answered a request
+    Simulated(SimulationOutput),
     /// an event to save the game in its current state to local storage
     SaveGame,
+    /// show the share code of the game
+    Share,
+    /// download the game in its current state as a file
+    ExportFile,
     /// user-triggererd event to pause the game
     Pause,
     /// an event to change the speed of in-game time
@@ -57,6 +98,12 @@ pub enum Msg {
     /// either pause or set speed to normal
     /// depending on current state
     ToggleSpeed,
+    /// a command issued through the keyboard
+    Command(Command),
+    /// the board was painted this many milliseconds after a tick
+    Painted(f64),
+    /// the page was hidden or shown again
+    VisibilityChange,
 }
 
 pub struct Game {
@@ -67,18 +114,20 @@ pub struct Game {
     /// The game is paused while so.
     modal: Option<Message>,
 
-    /// The full game state.
+    /// The full game state,
+    /// as last reported by the simulation.
     state: WorldState,
 
+    /// The save slot of this game, if it is being saved.
+    slot: Option<u32>,
+
     /// Whether audio is enabled.
     sound_enabled: bool,
 
-    /// Producer of random events.
-    reactor: EventReactor,
-
-    /// Whether to raise the humans a bit upwards
+    /// The ID of the task being dragged, if any.
+    /// Humans are raised a bit upwards meanwhile
     /// so that they are easier to assign tasks to.
-    bring_humans_up: bool,
+    dragged_task: Option<u32>,
 
     /// The currently presented alert message.
     alert_message: Option<String>,
@@ -86,8 +135,69 @@ pub struct Game {
     /// event dispatcher
     dispatch: Box<dyn Bridge<EventBus>>,
 
+    /// the worker running the simulation of the game
+    simulation: Box<dyn Bridge<Simulation>>,
+
+    /// The inputs sent to the simulation which it did not answer yet,
+    /// with the time at which each tick was sent.
+    in_flight: VecDeque<Option<f64>>,
+
+    /// Whether to save the game once the simulation
+    /// answered all inputs sent so far.
+    save_pending: bool,
+
     /// game watch, produces ticks over a time interval
     watch: GameWatch,
+
+    /// The ID of the task selected for keyboard control.
+    selected: Option<u32>,
+
+    /// Whether the selected task should receive focus after rendering.
+    focus_selected: bool,
+
+    /// The keydown listener added to the document.
+    keydown_listener: Option<js_sys::Function>,
+
+    /// The visibility change listener added to the document.
+    visibility_listener: Option<js_sys::Function>,
+
+    /// The latest thing which happened in the game,
+    /// announced to assistive technologies.
+    announcement: String,
+
+    /// What changed in the game since the board was last rendered.
+    pending_changes: ChangeSet,
+
+    /// The rendered tasks, by ID,
+    /// so that only those which changed are rendered again.
+    task_views: HashMap<u32, Html>,
+
+    /// The rendered team members, by ID,
+    /// as they are shown while no task is being dragged.
+    human_views: HashMap<u32, Html>,
+
+    /// The rendered event log.
+    timeline_view: Html,
+
+    /// The IDs and names of the team members, as given to tasks.
+    team_names: Rc<Vec<(u32, String)>>,
+
+    /// The IDs and names of those who left the team,
+    /// as given to team members.
+    alumni_names: Rc<Vec<(u32, String)>>,
+
+    /// The selected task and the time step
+    /// for which the rendered tasks are up to date.
+    views_of: (Option<u32>, u32),
+
+    /// The time at which the tick being rendered was sent, if measured.
+    painting: Option<f64>,
+
+    /// The pending request for the next animation frame.
+    paint_task: Option<RenderTask>,
+
+    /// The tick-to-paint times measured, if the overlay is shown.
+    perf: Option<PaintStats>,
 }
 
 impl Component for Game {
@@ -99,22 +209,22 @@ impl Component for Game {
         let dispatch = EventBus::bridge(link.callback(Msg::Event));
 
         // choose how to load the game
-        let state = match &props.state_from {
+        let (state, slot) = match &props.state_from {
             GameStateOrigin::New {
                 project_name,
                 onboarding,
             } => {
                 let state = WorldState::new(project_name.clone(), *onboarding);
-                state.save().unwrap_or_else(|e| {
-                    gloo_console::error!("Could not save game state:", e);
-                    gloo_console::error!("If you are reading this, try disabling adblockers and other browser shields.");
-                });
-                state
+                let slot = saves::create_slot(project_name, &state)
+                    .map_err(|e| {
+                        gloo_console::error!("Could not save game state:", e);
+                        gloo_console::error!("If you are reading this, try disabling adblockers and other browser shields.");
+                    })
+                    .ok();
+                (state, slot)
             }
-            GameStateOrigin::Continue => WorldState::load_from_storage()
-                .expect_throw("Could not load game :( If you are reading this, try disabling adblockers and other shields.")
-                .expect_throw("No save game! This is a bug, please inform author."),
-            GameStateOrigin::Dummy => WorldState::dummy(),
+            GameStateOrigin::Continue { slot, state } => (WorldState::clone(state), Some(*slot)),
+            GameStateOrigin::Dummy => (WorldState::dummy(), None),
         };
 
         let sound_enabled = crate::audio::is_enabled().unwrap_or_else(|e| {
@@ -122,7 +232,21 @@ impl Component for Game {
             false
         });
 
+        // start the simulation from the loaded state
+        let mut simulation =
+            Simulation::bridge(link.callback(|Json(output)| Msg::Simulated(output)));
+        simulation.send(Json(SimulationInput::Start {
+            state: Box::new(state.clone()),
+            locale: i18n::current_locale(),
+        }));
+
+        let clock = ClockSettings::load().unwrap_or_else(|e| {
+            gloo_console::error!("Could not load clock settings, using defaults:", e);
+            ClockSettings::default()
+        });
+
         let mut watch = GameWatch::new();
+        watch.set_max_catch_up_ticks(clock.max_catch_up_ticks);
 
         // start the watch
         {
@@ -131,174 +255,96 @@ impl Component for Game {
             watch.start_with(tick_fn);
         }
 
-        // bring tutorial in after a small while
-        if state.tutorial.is_some() {
-            let link = link.clone();
-            let tutorial_fn =
-                move || link.send_message(Msg::Event(EventBusRequest::AdvanceTutorial));
-            Timeout::new(700, tutorial_fn).forget();
-        }
-
-        // add keydown listener to document for sound toggling
-        {
+        // add keydown listener to document for keyboard controls
+        let keydown_listener = {
             let link = link.clone();
             let closure = Closure::wrap(Box::new(move |e: KeyboardEvent| {
-                {
+                if let Some(command) = keyboard::command_of(&e) {
                     let ev: &Event = e.as_ref();
                     ev.prevent_default();
-                }
-                if e.key() == " " {
-                    link.send_message(Msg::ToggleSpeed);
+                    link.send_message(Msg::Command(command));
                 }
             }) as Box<dyn FnMut(_)>)
             .into_js_value();
+            let listener: js_sys::Function = closure.unchecked_into();
 
             document()
-                .add_event_listener_with_callback(
-                    "keydown",
-                    closure.as_ref().unchecked_ref::<js_sys::Function>(),
-                )
-                .unwrap_or_else(|e| {
-                    gloo_console::error!("Could not add keydown listener for audio toggling:", e);
-                });
-        }
+                .add_event_listener_with_callback("keydown", &listener)
+                .map(|_| listener)
+                .map_err(|e| {
+                    gloo_console::error!(
+                        "Could not add keydown listener for keyboard controls:",
+                        e
+                    );
+                })
+                .ok()
+        };
 
-        Self {
+        // the game may pause when the player switches to another tab,
+        // if the clock settings say so when it happens
+        let visibility_listener = {
+            let link = link.clone();
+            let closure = Closure::wrap(
+                Box::new(move || link.send_message(Msg::VisibilityChange)) as Box<dyn FnMut()>
+            )
+            .into_js_value();
+            let listener: js_sys::Function = closure.unchecked_into();
+
+            document()
+                .add_event_listener_with_callback("visibilitychange", &listener)
+                .map(|_| listener)
+                .map_err(|e| {
+                    gloo_console::error!("Could not add visibility change listener:", e);
+                })
+                .ok()
+        };
+
+        let mut game = Self {
             link,
             props,
             modal: None,
             state,
+            slot,
             sound_enabled,
-            bring_humans_up: false,
+            dragged_task: None,
             alert_message: None,
-            reactor: EventReactor::new(),
             dispatch,
+            simulation,
+            in_flight: VecDeque::from(vec![None]),
+            save_pending: false,
             watch,
-        }
+            selected: None,
+            focus_selected: false,
+            keydown_listener,
+            visibility_listener,
+            announcement: String::new(),
+            pending_changes: ChangeSet::default(),
+            task_views: HashMap::new(),
+            human_views: HashMap::new(),
+            timeline_view: html! {},
+            team_names: Rc::new(Vec::new()),
+            alumni_names: Rc::new(Vec::new()),
+            views_of: (None, 0),
+            painting: None,
+            paint_task: None,
+            perf: None,
+        };
+        game.render_all();
+        game
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
-        match msg {
-            Msg::Pause => {
-                self.watch.pause();
-                true
-            }
-            Msg::SetGameSpeed(speed) => {
-                if Some(speed) != self.watch.current_speed() {
-                    self.update_speed(speed);
-                    true
-                } else {
-                    false
-                }
-            }
-            Msg::ToggleSpeed => {
-                play_zipclick();
-                if let Some(_) = self.watch.current_speed() {
-                    self.watch.pause();
-                    true
-                } else {
-                    self.update_speed(GameSpeed::Normal);
-                    true
-                }
-            }
-            Msg::ToggleSound => match crate::audio::set_audio(!self.sound_enabled) {
-                Ok(()) => {
-                    self.sound_enabled = !self.sound_enabled;
-                    if self.sound_enabled {
-                        play_zipclick();
-                    }
-                    true
-                }
-                Err(e) => {
-                    gloo_console::error!("Could not save audio settings: ", e);
-                    false
-                }
-            },
-            Msg::CloseModal => {
-                self.modal = None;
-                // resume game
-                let link = self.link.clone();
-                let tick_fn = move || link.send_message(Msg::Event(EventBusRequest::Tick));
-                self.watch.start_with(tick_fn);
-
-                true
-            }
-            Msg::OpenMessage(msg) => {
-                // apply modal
-                self.modal = Some(msg);
-                // pause the game
-                self.watch.pause();
-
-                true
-            }
-            Msg::HideAlert => {
-                self.alert_message = None;
-                true
-            }
-
-            Msg::Event(EventBusRequest::DragTaskStart(_task_id)) => {
-                self.bring_humans_up = true;
-                true
-            }
-
-            Msg::Event(EventBusRequest::DragTaskEnd(_task_id)) => {
-                self.bring_humans_up = false;
-                true
-            }
-
-            Msg::Event(event) => {
-                let updated = match event {
-                    EventBusRequest::AssignTask { .. } | EventBusRequest::MoveTask { .. } => {
-                        self.bring_humans_up = false;
-                        true
-                    }
-                    _ => false,
-                };
-
-                match self.state.apply_event(event, &mut self.reactor) {
-                    EventOutcome::Nothing => updated,
-                    EventOutcome::Update => true,
-                    EventOutcome::Alert(msg) => {
-                        // show alert in board
-                        self.alert_message = Some(msg.to_string());
-
-                        let link = self.link.clone();
-                        Timeout::new(3_500, move || link.send_message(Msg::HideAlert)).forget();
-
-                        true
-                    }
-                    EventOutcome::OpenMessage(msg) => {
-                        // apply modal
-                        self.modal = Some(msg);
-                        // pause the game
-                        self.watch.pause();
-                        true
-                    }
-                    EventOutcome::EndOfMonth(report) => {
-                        // apply modal
-                        self.modal = Some(Message::EndOfMonth(report));
-
-                        // play end of month tune
-                        play_endofmonth();
-
-                        // pause the game
-                        self.watch.pause();
-                        true
-                    }
+        let simulated = matches!(msg, Msg::Simulated(_));
+        let render = self.handle(msg);
+        if render {
+            let tasks_rendered = self.refresh_views();
+            if let Some(perf) = &mut self.perf {
+                if simulated {
+                    perf.tasks_rendered = tasks_rendered;
                 }
             }
-            Msg::EventWithTimeout { ms, event } => {
-                let link = self.link.clone();
-                let event_fn = move || link.send_message(Msg::Event(event));
-                Timeout::new(ms, event_fn).forget();
-
-                false
-            }
-            Msg::SaveGame => {
-                self.state.save().expect_throw("could not save game");
-                false
-            }
         }
+        render
     }
 
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
@@ -326,7 +372,7 @@ impl Component for Game {
                                 { message.clone() }
                             </p>
                         </div>
-                        <button onclick=click_handler>{ "OK" }</button>
+                        <button onclick=click_handler>{ tr("common.ok") }</button>
                     </Modal>
                 }
             }
@@ -341,151 +387,44 @@ impl Component for Game {
                     ]
                 });
 
-                let title = format!("End of Month {}", self.state.month);
+                let title = tr_count("modal.end_of_month", self.state.month as i64);
                 html! {
                     <Modal title=title>
                         <div class="modal-body">
                             {message.body()}
                         </div>
-                        <button onclick=click_handler>{ "OK" }</button>
+                        <button onclick=click_handler>{ tr("common.ok") }</button>
                     </Modal>
                 }
             }
-            Some(message @ Message::Tutorial(phase)) => {
-                match phase {
-                    // do not produce any modals
-                    0 => {
-                        html! {}
-                    }
-                    // advance automatically to next message
-                    1 | 2 => {
-                        let click_handler = self.link.callback(move |_| {
-                            play_zipclick();
-
-                            Msg::Event(EventBusRequest::AdvanceTutorial)
-                        });
-
-                        html! {
-                            <Modal title="Onboarding">
-                                <div class="modal-body">
-                                    { message.body() }
-                                </div>
-                                <button onclick=click_handler>{ "OK" }</button>
-                            </Modal>
-                        }
-                    }
-                    // perform timed stuff, then advance
-                    3 => {
-                        let click_handler = self.link.batch_callback(move |_| {
-                            play_zipclick();
-
-                            // create an easy task
-                            let task = GameTaskBuilder::new("Easy task", TaskKind::Normal, 5, 2);
-
-                            vec![
-                                Msg::CloseModal,
-                                // create an easy task
-                                Msg::EventWithTimeout {
-                                    ms: 750,
-                                    event: EventBusRequest::AddTask(task),
-                                },
-                                Msg::EventWithTimeout {
-                                    ms: 750 + 600,
-                                    event: EventBusRequest::AdvanceTutorial,
-                                },
-                            ]
-                        });
-
-                        html! {
-                            <Modal title="Onboarding">
-                                <div class="modal-body">
-                                    { message.body() }
-                                </div>
-                                <button onclick=click_handler>{ "OK" }</button>
-                            </Modal>
-                        }
-                    }
-                    // wait and add a bug task, then advance
-                    9 => {
-                        let click_handler = self.link.batch_callback(move |_| {
-                            play_zipclick();
-
-                            // create an easy task
-                            let task = GameTaskBuilder::new("Bug!", TaskKind::Bug, 2, 4);
-
-                            vec![
-                                Msg::CloseModal,
-                                // create an easy task
-                                Msg::EventWithTimeout {
-                                    ms: 2_800,
-                                    event: EventBusRequest::AddTask(task),
-                                },
-                                Msg::EventWithTimeout {
-                                    ms: 2_800 + 600,
-                                    event: EventBusRequest::AdvanceTutorial,
-                                },
-                            ]
-                        });
-
-                        html! {
-                            <Modal title="Onboarding">
-                                <div class="modal-body">
-                                    { message.body() }
-                                </div>
-                                <button onclick=click_handler>{ "OK" }</button>
-                            </Modal>
-                        }
-                    }
-                    // wait and add a chore task, then advance
-                    11 => {
-                        let click_handler = self.link.batch_callback(move |_| {
-                            play_zipclick();
-
-                            // create an easy task
-                            let task =
-                                GameTaskBuilder::new("Refactor stuff", TaskKind::Chore, 0, 3);
-
-                            vec![
-                                Msg::CloseModal,
-                                // create an easy task
-                                Msg::EventWithTimeout {
-                                    ms: 20_000,
-                                    event: EventBusRequest::AddTask(task),
-                                },
-                                Msg::EventWithTimeout {
-                                    ms: 21_000,
-                                    event: EventBusRequest::AdvanceTutorial,
-                                },
-                            ]
-                        });
-
-                        html! {
-                            <Modal title="Onboarding">
-                                <div class="modal-body">
-                                    { message.body() }
-                                </div>
-                                <button onclick=click_handler>{ "OK" }</button>
-                            </Modal>
-                        }
-                    }
-                    // all other, do not advance on close,
-                    // should instead wait for certain actions to be performed
-                    _ => {
-                        let click_handler = self.link.callback(move |_| {
-                            play_zipclick();
-
-                            Msg::CloseModal
-                        });
-
-                        html! {
-                            <Modal title="Onboarding">
-                                <div class="modal-body">
-                                    { message.body() }
-                                </div>
-                                <button onclick=click_handler>{ "OK" }</button>
-                            </Modal>
-                        }
+            Some(message @ Message::Tutorial(_)) => {
+                // advance on close if the next step is waiting for it,
+                // otherwise wait for certain actions to be performed
+                let advance = self
+                    .state
+                    .tutorial
+                    .map(tutorial::advances_on_acknowledge)
+                    .unwrap_or(false);
+                let click_handler = self.link.batch_callback(move |_| {
+                    play_zipclick();
+
+                    if advance {
+                        vec![
+                            Msg::CloseModal,
+                            Msg::Event(EventBusRequest::AdvanceTutorial),
+                        ]
+                    } else {
+                        vec![Msg::CloseModal]
                     }
+                });
+
+                html! {
+                    <Modal title=tr("modal.onboarding")>
+                        <div class="modal-body">
+                            { message.body() }
+                        </div>
+                        <button onclick=click_handler>{ tr("common.ok") }</button>
+                    </Modal>
                 }
             }
             Some(msg @ Message::Ceo { .. }) => {
@@ -504,22 +443,87 @@ impl Component for Game {
                 });
 
                 html! {
-                    <Modal title="A message from the CEO">
+                    <Modal title=tr("modal.ceo")>
                         <div class="modal-body">
                             { msg.body() }
                         </div>
-                        <button onclick=accept_handler>{ "Accept" }</button>
-                        <button onclick=decline_handler>{ "Decline" }</button>
+                        <button onclick=accept_handler>{ tr("ceo.accept") }</button>
+                        <button onclick=decline_handler>{ tr("ceo.decline") }</button>
                     </Modal>
                 }
             }
             Some(msg @ Message::EndMessage(..)) => {
                 // render the modal without any buttons
                 html! {
-                    <Modal title="Offer Accepted">
+                    <Modal title=tr("modal.offer_accepted")>
+                        <div class="modal-body">
+                            { msg.body() }
+                        </div>
+                    </Modal>
+                }
+            }
+            Some(msg @ Message::Share { .. }) => {
+                let download_handler = self.link.callback(move |_| {
+                    play_zipclick();
+                    Msg::ExportFile
+                });
+                let click_handler = self.link.callback(move |_| {
+                    play_zipclick();
+                    Msg::CloseModal
+                });
+
+                html! {
+                    <Modal title=tr("share.title")>
+                        <div class="modal-body">
+                            { msg.body() }
+                        </div>
+                        <button onclick=download_handler>{ tr("share.download") }</button>
+                        <button onclick=click_handler>{ tr("common.ok") }</button>
+                    </Modal>
+                }
+            }
+            Some(msg @ Message::Help) => {
+                let click_handler = self.link.callback(move |_| {
+                    play_zipclick();
+                    Msg::CloseModal
+                });
+
+                html! {
+                    <Modal title=tr("keys.title")>
+                        <div class="modal-body">
+                            { msg.body() }
+                        </div>
+                        <button onclick=click_handler>{ tr("common.ok") }</button>
+                    </Modal>
+                }
+            }
+            Some(msg @ Message::Debug(..)) => {
+                let click_handler = self.link.callback(move |_| {
+                    play_zipclick();
+                    Msg::CloseModal
+                });
+
+                html! {
+                    <Modal title=tr("debug.title")>
+                        <div class="modal-body">
+                            { msg.body() }
+                        </div>
+                        <button onclick=click_handler>{ tr("common.ok") }</button>
+                    </Modal>
+                }
+            }
+            Some(msg @ Message::Dashboard(..)) => {
+                let click_handler = self.link.callback(move |_| {
+                    play_zipclick();
+                    Msg::CloseModal
+                });
+
+                html! {
+                    <Modal title=tr("dashboard.title")>
                         <div class="modal-body">
                             { msg.body() }
                         </div>
+                        <button onclick=click_handler>{ tr("common.ok") }</button>
                     </Modal>
                 }
             }
@@ -534,62 +538,64 @@ impl Component for Game {
                         <div class="modal-body">
                             { msg.body() }
                         </div>
-                        <button onclick=click_handler>{ "OK" }</button>
+                        <button onclick=click_handler>{ tr("common.ok") }</button>
                     </Modal>
                 }
             }
             None => html! {},
         };
 
-        let state = &self.state;
-
-        let backlog_tasks = state
-            .tasks_backlog
-            .iter()
-            .map(|t| self.render_task(t))
-            .collect::<Vec<_>>();
-        let candidate_tasks = state
-            .tasks_candidate
-            .iter()
-            .map(|t| self.render_task(t))
-            .collect::<Vec<_>>();
-        let progress_tasks = state
-            .tasks_progress
-            .iter()
-            .map(|t| self.render_task(t))
-            .collect::<Vec<_>>();
-        let review_tasks = state
-            .tasks_review
-            .iter()
-            .map(|t| self.render_task(t))
-            .collect::<Vec<_>>();
-        let done_tasks = state
-            .tasks_done
-            .iter()
-            .filter(|t| t.visible)
-            .map(|t| self.render_task(t))
-            .collect::<Vec<_>>();
+        let backlog_tasks = self.stage_views(StageId::Backlog);
+        let candidate_tasks = self.stage_views(StageId::Candidate);
+        let progress_tasks = self.stage_views(StageId::Progress);
+        let review_tasks = self.stage_views(StageId::Review);
+        let done_tasks = self.stage_views(StageId::Done);
 
         let month = self.state.month;
         let time = self.state.time_in_month;
 
-        let hr_desc = match self.state.humans.iter().filter(|h| !h.quit).count() {
-            0 => format!("no humans (WAT)"),
-            1 => format!("1 lead"),
-            2 => format!("1 lead + 1 developer"),
-            n => format!("1 lead + {} developers", n),
+        let hr_desc = match self.state.humans.team_size() {
+            0 => tr("hr.none"),
+            n => tr_count("hr.team", n as i64 - 1),
         };
 
-        let bring_humans_up = self.bring_humans_up;
+        let dragged = self.dragged_task.and_then(|id| self.task_transfer(id));
+        let bring_humans_up = dragged.is_some();
 
+        // humans give hints on whether the dragged task can go to them
         let humans = self
             .state
             .humans
-            .iter()
-            .filter(|human| !human.quit)
-            .map(|human| html!(<Human id=human.id name=&human.name status=human.status color=&human.color bring_up=bring_humans_up />))
+            .team()
+            .map(|human| match &dragged {
+                Some(task) => {
+                    let drop_hint = Some(self.state.can_assign(task, human.id));
+                    html!(<Human id=human.id name=human.display_name() status=human.status color=&human.color bring_up=bring_humans_up drop_hint=drop_hint alumni=self.alumni_names.clone() />)
+                }
+                None => self
+                    .human_views
+                    .get(&human.id)
+                    .cloned()
+                    .unwrap_or_else(|| self.render_human(human.id)),
+            })
             .collect::<Vec<_>>();
 
+        // the unassign zone only shows up while a task is being dragged
+        let unassign = match &dragged {
+            Some(task) => html! {
+                <Unassign drop_hint=Some(self.state.can_unassign(task)) />
+            },
+            None => html! {},
+        };
+
+        // the move into the task's own stage is no move at all
+        let stage_hint = |to: StageId| {
+            dragged
+                .as_ref()
+                .map(|task| self.state.can_move(task, to))
+                .filter(|hint| *hint != Err(MoveError::SameStage))
+        };
+
         let pause_handler = self.link.callback(move |_| {
             play_zipclick();
             Msg::Pause
@@ -612,6 +618,22 @@ impl Component for Game {
 
         let sound_handler = self.link.callback(move |_| Msg::ToggleSound);
 
+        let share_handler = self.link.callback(move |_| {
+            play_zipclick();
+            Msg::Share
+        });
+
+        let help_handler = self.link.callback(move |_| {
+            play_zipclick();
+            Msg::OpenMessage(Message::Help)
+        });
+
+        let samples = self.state.samples.clone();
+        let dashboard_handler = self.link.callback(move |_| {
+            play_zipclick();
+            Msg::OpenMessage(Message::Dashboard(samples.clone()))
+        });
+
         let (class_paused, class_normal, class_fast, class_faster) =
             match self.watch.current_speed() {
                 None => ("speed-paused", "", "speed-fast", "speed-faster"),
@@ -620,66 +642,353 @@ impl Component for Game {
                 Some(GameSpeed::Faster) => ("", "", "speed-fast", "speed-faster speed-set"),
             };
 
+        let perf = match &self.perf {
+            Some(stats) => html! { <PerfOverlay stats=stats.clone() /> },
+            None => html! {},
+        };
+
         let sound_icon = if self.sound_enabled { "🕪" } else { "🕨" };
 
         let sound_tooltip = if self.sound_enabled {
-            "Audio is enabled; press to disable"
+            tr("status.sound_enabled")
         } else {
-            "Audio is disabled; press to enable"
+            tr("status.sound_disabled")
         };
 
         html! {
             <>
                 <div class="status-top">
                     <button class="btn-sound" title=sound_tooltip onclick=sound_handler>{sound_icon}</button>
-                    { "Month " } {month}
+                    { tr_count("status.month", month as i64) }
                     <button class=class_paused onclick=pause_handler>{"⏸"}</button>
                     <button class=class_normal onclick=normal_speed_handler>{"▶"}</button>
                     <button class=class_fast onclick=fast_speed_handler>{"▶▶"}</button>
                     <button class=class_faster onclick=faster_speed_handler>{"▶▶▶"}</button>
                     <div class="status-score">{self.state.total_score / 1_000}</div>
+                    <button class="btn-share" title=tr("status.share") onclick=share_handler>{"⇪"}</button>
+                    <button class="btn-dashboard" title=tr("status.dashboard") onclick=dashboard_handler>{"📈"}</button>
+                    <button class="btn-help" title=tr("status.keys") onclick=help_handler>{"⌨"}</button>
                     <Clock time=time />
                 </div>
                 <Board product_name=self.state.product_name.clone() alert_message=self.alert_message.clone()>
-                    <Stage id=StageId::Backlog description="Backlog">
+                    <Stage id=StageId::Backlog description=tr("stage.backlog") drop_hint=stage_hint(StageId::Backlog)>
                         {backlog_tasks}
                     </Stage>
-                    <Stage id=StageId::Candidate description="Sprint candidate">
+                    <Stage id=StageId::Candidate description=tr("stage.candidate") drop_hint=stage_hint(StageId::Candidate)>
                         {candidate_tasks}
                     </Stage>
-                    <Stage id=StageId::Progress description="In progress">
+                    <Stage id=StageId::Progress description=tr("stage.progress") drop_hint=stage_hint(StageId::Progress)>
                         {progress_tasks}
                     </Stage>
-                    <Stage id=StageId::Review description="Under review">
+                    <Stage id=StageId::Review description=tr("stage.review") drop_hint=stage_hint(StageId::Review)>
                         {review_tasks}
                     </Stage>
-                    <Stage id=StageId::Done description="Done">
+                    <Stage id=StageId::Done description=tr("stage.done") drop_hint=stage_hint(StageId::Done)>
                         {done_tasks}
                     </Stage>
                 </Board>
-                <div class="human-resources">
-                    <div class="human-resources-header">{ "Human Resources: " } { hr_desc }</div>
+                <div class="human-resources" role="list" aria-label=tr("hr.header")>
+                    <div class="human-resources-header" aria-hidden="true">{ tr("hr.header") } { hr_desc }</div>
                     // render humans based on state
                     { humans }
+                    { unassign }
                 </div>
+                { self.timeline_view.clone() }
+                <div class="visually-hidden" role="status" aria-live="polite">{&self.announcement}</div>
+                { perf }
 
                 {modal}
             </>
         }
     }
 
+    fn rendered(&mut self, _first_render: bool) {
+        if let (Some(sent), None) = (self.painting.take(), &self.paint_task) {
+            let painted = self
+                .link
+                .callback(move |_| Msg::Painted(js_sys::Date::now() - sent));
+            self.paint_task = Some(RenderService::request_animation_frame(painted));
+        }
+
+        if !self.focus_selected {
+            return;
+        }
+        self.focus_selected = false;
+
+        let element = self
+            .selected
+            .and_then(|id| document().get_element_by_id(&format!("task_T{}", id)))
+            .and_then(|e| e.dyn_into::<web_sys::HtmlElement>().ok());
+        if let Some(element) = element {
+            element.focus().unwrap_or_else(|e| {
+                gloo_console::error!("Could not focus task:", e);
+            });
+        }
+    }
+
     fn destroy(&mut self) {
+        if let Some(listener) = self.keydown_listener.take() {
+            document()
+                .remove_event_listener_with_callback("keydown", &listener)
+                .unwrap_or_else(|e| {
+                    gloo_console::error!("Could not remove keydown listener:", e);
+                });
+        }
+        if let Some(listener) = self.visibility_listener.take() {
+            document()
+                .remove_event_listener_with_callback("visibilitychange", &listener)
+                .unwrap_or_else(|e| {
+                    gloo_console::error!("Could not remove visibility change listener:", e);
+                });
+        }
+
         // try to save game
-        gloo_console::log!("Saving game...");
-        self.state.save().expect_throw("Could not save game");
+        if let Some(slot) = self.slot {
+            gloo_console::log!("Saving game...");
+            self.state.save(slot).expect_throw("Could not save game");
+        }
     }
 }
 
 impl Game {
-    fn start_watch(&mut self) {
-        let link = self.link.clone();
-        let tick_fn = move || link.send_message(Msg::Event(EventBusRequest::Tick));
-        self.watch.start_with(tick_fn);
+    /// Handle a message, returning whether to render again.
+    fn handle(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Pause => {
+                self.watch.pause();
+                true
+            }
+            Msg::SetGameSpeed(speed) => {
+                if Some(speed) != self.watch.current_speed() {
+                    self.update_speed(speed);
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::ToggleSpeed => {
+                play_zipclick();
+                if let Some(_) = self.watch.current_speed() {
+                    self.watch.pause();
+                    true
+                } else {
+                    self.update_speed(GameSpeed::Normal);
+                    true
+                }
+            }
+            Msg::ToggleSound => match crate::audio::set_audio(!self.sound_enabled) {
+                Ok(()) => {
+                    self.sound_enabled = !self.sound_enabled;
+                    if self.sound_enabled {
+                        play_zipclick();
+                    }
+                    true
+                }
+                Err(e) => {
+                    gloo_console::error!("Could not save audio settings: ", e);
+                    false
+                }
+            },
+            Msg::CloseModal => {
+                self.modal = None;
+                // resume game
+                let link = self.link.clone();
+                let tick_fn = move || link.send_message(Msg::Event(EventBusRequest::Tick));
+                self.watch.start_with(tick_fn);
+                self.simulate(SimulationInput::Resume);
+
+                true
+            }
+            Msg::OpenMessage(msg) => {
+                // apply modal
+                self.modal = Some(msg);
+                // pause the game
+                self.watch.pause();
+
+                true
+            }
+            Msg::HideAlert => {
+                self.alert_message = None;
+                true
+            }
+
+            Msg::Event(EventBusRequest::DragTaskStart(task_id)) => {
+                self.dragged_task = Some(task_id);
+                true
+            }
+
+            Msg::Event(EventBusRequest::DragTaskEnd(_task_id)) => {
+                self.dragged_task = None;
+                true
+            }
+
+            Msg::Event(EventBusRequest::SelectTask(task_id)) => {
+                if self.selected != Some(task_id) {
+                    self.selected = Some(task_id);
+                    true
+                } else {
+                    false
+                }
+            }
+
+            Msg::Command(command) => {
+                // the board is not in reach while a modal is open
+                if self.modal.is_some() {
+                    return false;
+                }
+                self.run_command(command)
+            }
+
+            Msg::Event(event) => {
+                let updated = match event {
+                    EventBusRequest::AssignTask { .. }
+                    | EventBusRequest::UnassignTask { .. }
+                    | EventBusRequest::AssignReview { .. }
+                    | EventBusRequest::MoveTask { .. } => {
+                        self.dragged_task = None;
+                        true
+                    }
+                    // the board is not in reach while a modal is open
+                    EventBusRequest::Undo | EventBusRequest::Redo if self.modal.is_some() => {
+                        return false;
+                    }
+                    _ => false,
+                };
+
+                self.simulate(SimulationInput::Request(event));
+                updated
+            }
+            Msg::Simulated(SimulationOutput {
+                patch,
+                outcome,
+                changed,
+            }) => {
+                let sent = self.in_flight.pop_front().flatten();
+
+                let last_entry = self.state.log.last().cloned();
+                patch.apply_to(&mut self.state);
+                if self.state.log.last() != last_entry.as_ref() {
+                    if let Some(entry) = self.state.log.last() {
+                        self.announcement = entry.text();
+                    }
+                }
+
+                if self.save_pending && self.in_flight.is_empty() {
+                    self.save_pending = false;
+                    self.save();
+                }
+
+                let render = !changed.is_empty();
+                self.pending_changes.merge(changed);
+
+                let render = match outcome {
+                    EventOutcome::Nothing => render,
+                    EventOutcome::Update => true,
+                    EventOutcome::Alert(msg) => {
+                        // show alert in board
+                        self.alert_message = Some(tr(&msg));
+
+                        let link = self.link.clone();
+                        Timeout::new(3_500, move || link.send_message(Msg::HideAlert)).forget();
+
+                        true
+                    }
+                    EventOutcome::OpenMessage(msg) => {
+                        // apply modal
+                        self.modal = Some(msg);
+                        // pause the game
+                        self.watch.pause();
+                        true
+                    }
+                    EventOutcome::EndOfMonth(report) => {
+                        // apply modal
+                        self.modal = Some(Message::EndOfMonth(report));
+
+                        // play end of month tune
+                        play_endofmonth();
+
+                        // pause the game
+                        self.watch.pause();
+                        true
+                    }
+                };
+
+                // measure the time until the outcome of the tick is painted
+                if let (true, Some(sent), Some(_)) = (render, sent, &self.perf) {
+                    self.painting.get_or_insert(sent);
+                }
+                render
+            }
+            Msg::Painted(ms) => {
+                self.paint_task = None;
+                match &mut self.perf {
+                    Some(perf) => {
+                        perf.record(ms);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Msg::VisibilityChange => {
+                let pause_when_hidden = ClockSettings::load()
+                    .map(|clock| clock.pause_when_hidden)
+                    .unwrap_or_else(|e| {
+                        gloo_console::error!("Could not load clock settings:", e);
+                        false
+                    });
+                // the player resumes the game on their own
+                if pause_when_hidden && document().hidden() && self.watch.current_speed().is_some()
+                {
+                    self.watch.pause();
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::SaveGame => {
+                // wait for the simulation to catch up with what was sent before
+                if self.in_flight.is_empty() {
+                    self.save();
+                } else {
+                    self.save_pending = true;
+                }
+                false
+            }
+            Msg::Share => match share::to_code(&self.state) {
+                Ok(code) => {
+                    self.link
+                        .send_message(Msg::OpenMessage(Message::Share { code }));
+                    false
+                }
+                Err(e) => {
+                    gloo_console::error!("Could not produce share code:", e.to_string());
+                    false
+                }
+            },
+            Msg::ExportFile => {
+                share::download_state(&self.state).unwrap_or_else(|e| {
+                    gloo_console::error!("Could not export game:", e);
+                });
+                false
+            }
+        }
+    }
+
+    /// Send an input to the simulation, which answers it later on.
+    fn simulate(&mut self, input: SimulationInput) {
+        let sent = match input {
+            SimulationInput::Request(EventBusRequest::Tick) => Some(js_sys::Date::now()),
+            _ => None,
+        };
+        self.in_flight.push_back(sent);
+        self.simulation.send(Json(input));
+    }
+
+    /// Save the game in its current state to local storage.
+    fn save(&self) {
+        if let Some(slot) = self.slot {
+            self.state.save(slot).expect_throw("could not save game");
+        }
     }
 
     fn update_speed(&mut self, speed: GameSpeed) {
@@ -691,7 +1000,7 @@ impl Game {
     fn assigned_of(&self, task: &GameTask) -> Option<(u32, String, String)> {
         if let Some(id) = task.assigned {
             let human = self.state.human_of(id);
-            human.map(|h| (h.id, h.name.to_string(), h.color.to_string()))
+            human.map(|h| (h.id, h.display_name().to_string(), h.color.to_string()))
         } else {
             None
         }
@@ -700,15 +1009,266 @@ impl Game {
     fn render_task(&self, t: &GameTask) -> Html {
         let assigned = self.assigned_of(&t);
 
+        // times are only rendered again once per time step
+        let now = self.views_of.1 * TIME_STEP;
         let deadline_ratio = t.deadline.map(|deadline| {
-            let expected_time = self.state.time.saturating_sub(t.created).max(1);
-            let time_left = deadline.saturating_sub(self.state.time);
+            let expected_time = now.saturating_sub(t.created).max(1);
+            let time_left = deadline.saturating_sub(now);
             time_left as f32 / expected_time as f32
         });
 
         html!(<Task key=t.id
-            id=t.id kind=t.kind stage=t.stage assigned=assigned
+            id=t.id description=self.state.task_description(t) kind=t.kind stage=t.stage assigned=assigned
             bugs_found=t.bugs_found score=t.score progress=t.progress
-            deadline_ratio=deadline_ratio />)
+            deadline_ratio=deadline_ratio timings=t.timings(now)
+            selected=self.selected == Some(t.id) team=self.team_names.clone() />)
+    }
+
+    /// Render a team member as shown while no task is being dragged.
+    fn render_human(&self, id: u32) -> Html {
+        match self.state.humans.member(id) {
+            Some(human) => {
+                html!(<Human id=human.id name=human.display_name() status=human.status color=&human.color bring_up=false drop_hint=None alumni=self.alumni_names.clone() />)
+            }
+            None => html! {},
+        }
+    }
+
+    /// The rendered tasks shown in the given stage, from top to bottom.
+    fn stage_views(&self, stage: StageId) -> Vec<Html> {
+        self.stage_tasks(stage)
+            .into_iter()
+            .map(|t| {
+                self.task_views
+                    .get(&t.id)
+                    .cloned()
+                    .unwrap_or_else(|| self.render_task(t))
+            })
+            .collect()
+    }
+
+    /// Render the whole board again.
+    fn render_all(&mut self) {
+        self.pending_changes = ChangeSet::default();
+        self.views_of = (self.selected, self.state.time / TIME_STEP);
+        self.team_names = Rc::new(
+            self.state
+                .humans
+                .team()
+                .map(|h| (h.id, h.display_name().to_string()))
+                .collect(),
+        );
+        self.alumni_names = Rc::new(
+            self.state
+                .humans
+                .alumni()
+                .map(|h| (h.id, h.display_name().to_string()))
+                .collect(),
+        );
+        self.task_views = self
+            .state
+            .tasks
+            .iter()
+            .map(|t| (t.id, self.render_task(t)))
+            .collect();
+        self.human_views = self
+            .state
+            .humans
+            .team()
+            .map(|h| (h.id, self.render_human(h.id)))
+            .collect();
+        self.timeline_view = html! { <Timeline entries=self.state.log.clone() /> };
+    }
+
+    /// Render again the parts of the board which changed,
+    /// returning the number of tasks rendered.
+    fn refresh_views(&mut self) -> usize {
+        let changed = std::mem::take(&mut self.pending_changes);
+
+        // a new team changes the options of every task
+        if changed.team {
+            self.render_all();
+            return self.task_views.len();
+        }
+
+        let (selected_before, step_before) = self.views_of;
+        self.views_of = (self.selected, self.state.time / TIME_STEP);
+
+        let mut tasks = changed.tasks;
+        if selected_before != self.selected {
+            tasks.extend(selected_before);
+            tasks.extend(self.selected);
+        }
+        if step_before != self.views_of.1 {
+            // the times shown on open tasks and deadlines move on
+            tasks.extend(
+                self.state
+                    .tasks
+                    .iter()
+                    .filter(|t| t.stage != StageId::Done || t.deadline.is_some())
+                    .map(|t| t.id),
+            );
+        }
+
+        for id in &tasks {
+            match self.state.tasks.get(*id) {
+                Some(task) => {
+                    let view = self.render_task(task);
+                    self.task_views.insert(*id, view);
+                }
+                None => {
+                    self.task_views.remove(id);
+                }
+            }
+        }
+
+        for id in changed.humans {
+            let view = self.render_human(id);
+            self.human_views.insert(id, view);
+        }
+
+        if changed.log {
+            self.timeline_view = html! { <Timeline entries=self.state.log.clone() /> };
+        }
+
+        tasks.len()
+    }
+
+    /// The tasks shown in the given stage, from top to bottom.
+    fn stage_tasks(&self, stage: StageId) -> Vec<&GameTask> {
+        self.state.tasks.in_stage(stage).collect()
+    }
+
+    /// The selected task, if it is still on the board.
+    fn selected_task(&self) -> Option<&GameTask> {
+        self.task_of(self.selected?)
+    }
+
+    /// The task of the given ID shown on the board.
+    fn task_of(&self, id: u32) -> Option<&GameTask> {
+        self.state.tasks.get(id)
+    }
+
+    /// Move the selection across the board
+    /// by the given number of stages and tasks.
+    fn move_selection(&mut self, stages: isize, tasks: isize) -> ShouldRender {
+        let current = self.selected_task().and_then(|task| {
+            let column = STAGES.iter().position(|s| *s == task.stage)?;
+            let row = self
+                .stage_tasks(task.stage)
+                .iter()
+                .position(|t| t.id == task.id)?;
+            Some((column, row))
+        });
+
+        let target = match current {
+            // start from the first task on the board
+            None => STAGES
+                .iter()
+                .find_map(|stage| self.stage_tasks(*stage).first().map(|t| t.id)),
+            Some((column, row)) if stages == 0 => {
+                let tasks_in_stage = self.stage_tasks(STAGES[column]);
+                let row = (row as isize + tasks).clamp(0, tasks_in_stage.len() as isize - 1);
+                tasks_in_stage.get(row as usize).map(|t| t.id)
+            }
+            Some((column, row)) => {
+                // skip over empty stages
+                let mut column = column as isize + stages;
+                let mut target = None;
+                while target.is_none() && column >= 0 && (column as usize) < STAGES.len() {
+                    let tasks_in_stage = self.stage_tasks(STAGES[column as usize]);
+                    target = tasks_in_stage
+                        .get(row.min(tasks_in_stage.len().saturating_sub(1)))
+                        .map(|t| t.id);
+                    column += stages;
+                }
+                target
+            }
+        };
+
+        match target {
+            Some(id) if self.selected != Some(id) => {
+                self.selected = Some(id);
+                self.focus_selected = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The selected task as it would be dragged.
+    fn selected_transfer(&self) -> Option<TaskTransfer> {
+        self.task_transfer(self.selected?)
+    }
+
+    /// The transfer data for the task of the given ID, wherever it is.
+    fn task_transfer(&self, id: u32) -> Option<TaskTransfer> {
+        self.task_of(id).map(|task| TaskTransfer {
+            id: task.id,
+            kind: task.kind,
+            from: task.stage,
+            progress: task.progress,
+        })
+    }
+
+    fn run_command(&mut self, command: Command) -> ShouldRender {
+        match command {
+            Command::SelectUp => self.move_selection(0, -1),
+            Command::SelectDown => self.move_selection(0, 1),
+            Command::SelectLeft => self.move_selection(-1, 0),
+            Command::SelectRight => self.move_selection(1, 0),
+            Command::Deselect => self.selected.take().is_some(),
+            Command::MoveTo(to) => match self.selected_transfer() {
+                Some(task) if task.from != to => {
+                    self.focus_selected = true;
+                    self.update(Msg::Event(EventBusRequest::MoveTask { task, to }))
+                }
+                _ => false,
+            },
+            Command::AssignTo(index) => {
+                let human_id = self.state.humans.team().nth(index).map(|h| h.id);
+                match (self.selected_transfer(), human_id) {
+                    (Some(task), Some(human_id)) => {
+                        self.update(Msg::Event(EventBusRequest::AssignTask { task, human_id }))
+                    }
+                    _ => false,
+                }
+            }
+            Command::Unassign => match self.selected_transfer() {
+                Some(task) => self.update(Msg::Event(EventBusRequest::UnassignTask { task })),
+                None => false,
+            },
+            Command::ToggleSpeed => self.update(Msg::ToggleSpeed),
+            Command::Faster => {
+                play_zipclick();
+                match self.watch.current_speed() {
+                    None => self.update(Msg::SetGameSpeed(GameSpeed::Normal)),
+                    Some(GameSpeed::Normal) => self.update(Msg::SetGameSpeed(GameSpeed::Fast)),
+                    Some(_) => self.update(Msg::SetGameSpeed(GameSpeed::Faster)),
+                }
+            }
+            Command::Slower => {
+                play_zipclick();
+                match self.watch.current_speed() {
+                    None | Some(GameSpeed::Normal) => self.update(Msg::Pause),
+                    Some(GameSpeed::Fast) => self.update(Msg::SetGameSpeed(GameSpeed::Normal)),
+                    Some(GameSpeed::Faster) => self.update(Msg::SetGameSpeed(GameSpeed::Fast)),
+                }
+            }
+            Command::Undo => self.update(Msg::Event(EventBusRequest::Undo)),
+            Command::Redo => self.update(Msg::Event(EventBusRequest::Redo)),
+            Command::Help => self.update(Msg::OpenMessage(Message::Help)),
+            Command::Debug => {
+                let stats = SaveStats::of(&self.state);
+                self.update(Msg::OpenMessage(Message::Debug(stats)))
+            }
+            Command::PerfOverlay => {
+                self.perf = match self.perf.take() {
+                    Some(_) => None,
+                    None => Some(PaintStats::default()),
+                };
+                true
+            }
+        }
     }
 }
@@ -1,14 +1,22 @@
 use std::{
     borrow::Cow,
     fmt::{Display, Formatter},
+    rc::Rc,
 };
 
 use serde::{Deserialize, Serialize};
-use yew::{agent::Dispatcher, prelude::*};
+use wasm_bindgen::JsCast;
+use yew::{agent::Dispatcher, prelude::*, web_sys};
 
 use crate::{
-    data_transfer::{payload::TaskTransfer, DataTransfer, DragEffect},
+    components::task::{focus_first_item, menu_keydown},
+    data_transfer::{
+        payload::{HumanTransfer, TaskTransfer},
+        DataTransfer, DragEffect,
+    },
     event_bus::{EventBus, EventBusRequest},
+    i18n::{tr, tr_vars},
+    state::{GameError, MoveError},
 };
 
 /// A human resource.
@@ -26,14 +34,38 @@ pub struct GameHuman {
     pub experience: u32,
     /// the progress of the human at doing something
     pub progress: f32,
-    /// whether the human quit the team and is not coming back
+    /// the last month of the human's contract,
+    /// or `None` if they are a permanent member of the team
     #[serde(default)]
-    #[serde(skip_serializing_if = "is_false")]
-    pub quit: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_until: Option<u32>,
+    /// what the human did in the current month
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HumanStats::is_empty")]
+    pub stats: HumanStats,
+}
+
+/// What a human did during a month.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HumanStats {
+    /// the number of tasks fully developed
+    pub tasks_developed: u32,
+    /// the number of tasks which left review while assigned to the human
+    pub reviews_done: u32,
+    /// the number of bugs introduced while developing
+    pub bugs_introduced: u32,
+    /// the number of bugs found while reviewing
+    pub bugs_caught: u32,
+    /// the number of ticks in which the human did not work
+    pub idle_ticks: u32,
+    /// the experience gained
+    pub experience_gained: u32,
 }
 
-fn is_false(x: &bool) -> bool {
-    !x
+impl HumanStats {
+    fn is_empty(&self) -> bool {
+        *self == HumanStats::default()
+    }
 }
 
 impl GameHuman {
@@ -51,7 +83,17 @@ impl GameHuman {
             status: HumanStatus::Idle,
             experience: initial_experience,
             progress: 0.,
-            quit: false,
+            contract_until: None,
+            stats: HumanStats::default(),
+        }
+    }
+
+    /// The name of the human as presented to the player.
+    pub fn display_name(&self) -> Cow<'static, str> {
+        if self.id == 0 {
+            tr("human.you").into()
+        } else {
+            self.name.clone()
         }
     }
 }
@@ -69,6 +111,14 @@ pub struct Props {
     pub status: HumanStatus,
     /// whether to bring humans upwards for assignment
     pub bring_up: bool,
+    /// Whether the task being dragged could be assigned to this human,
+    /// or `None` if no task is being dragged
+    #[prop_or_default]
+    pub drop_hint: Option<Result<(), MoveError>>,
+    /// The humans who left the team and may be brought back
+    /// (ID and name), listed in the human's action menu
+    #[prop_or_default]
+    pub alumni: Rc<Vec<(u32, String)>>,
 }
 
 /// A status of the human.
@@ -105,20 +155,27 @@ pub struct Human {
     props: Props,
     link: ComponentLink<Self>,
     event_bus: Dispatcher<EventBus>,
+    /// whether the action menu is open
+    menu_open: bool,
+    /// the human's element
+    node: NodeRef,
+    /// the action menu's element
+    menu: NodeRef,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Msg {
     Nothing,
     Assign(TaskTransfer),
-}
-
-/// The data transferred when dragging a human to a drop zone.
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-pub struct HumanTransfer {
-    /// the unique user ID (0 is You)
-    pub id: u32,
-    pub name: String,
+    DropFailed(GameError),
+    /// Open the action menu, or close it if open.
+    ToggleMenu,
+    /// Close the action menu.
+    CloseMenu,
+    /// Let the human go from the team (from the action menu).
+    LayOff,
+    /// Bring back a human who left the team (from the action menu).
+    Rehire(u32),
 }
 
 impl Component for Human {
@@ -130,6 +187,9 @@ impl Component for Human {
             props,
             link,
             event_bus: EventBus::dispatcher(),
+            menu_open: false,
+            node: NodeRef::default(),
+            menu: NodeRef::default(),
         }
     }
 
@@ -147,6 +207,46 @@ impl Component for Human {
                 });
                 true
             }
+            Msg::DropFailed(e) => {
+                self.event_bus.send(EventBusRequest::ReportError(e));
+                false
+            }
+            Msg::ToggleMenu => {
+                // only open the menu if there is something to do
+                let has_actions = self.props.id != 0 || !self.props.alumni.is_empty();
+                self.menu_open = !self.menu_open && has_actions;
+                true
+            }
+            Msg::CloseMenu => {
+                let was_open = self.menu_open;
+                self.menu_open = false;
+                was_open
+            }
+            Msg::LayOff => {
+                self.menu_open = false;
+                self.event_bus.send(EventBusRequest::LayOff {
+                    human_id: self.props.id,
+                });
+                true
+            }
+            Msg::Rehire(human_id) => {
+                self.menu_open = false;
+                self.event_bus.send(EventBusRequest::Rehire { human_id });
+                true
+            }
+        }
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        // bring focus into the menu once opened
+        if self.menu_open {
+            let menu_has_focus = yew::utils::document()
+                .active_element()
+                .and_then(|active| active.closest("[role=menu]").ok().flatten())
+                .is_some();
+            if !menu_has_focus {
+                focus_first_item(&self.menu);
+            }
         }
     }
 
@@ -169,13 +269,14 @@ impl Component for Human {
             }
             Msg::Nothing
         });
-        let dragover_handler = self.link.callback(|ev: DragEvent| {
-            {
+        let accepts_task = !matches!(self.props.drop_hint, Some(Err(_)));
+        let dragover_handler = self.link.callback(move |ev: DragEvent| {
+            if accepts_task {
                 let ev: &Event = ev.as_ref();
                 ev.prevent_default();
+                let data_transfer = DataTransfer::from_event(&ev);
+                data_transfer.set_drop_effect(DragEffect::Link);
             }
-            let data_transfer = DataTransfer::from_event(&ev);
-            data_transfer.set_drop_effect(DragEffect::Link);
             Msg::Nothing
         });
         let drop_handler = self.link.callback(move |ev: DragEvent| {
@@ -186,22 +287,64 @@ impl Component for Human {
             // get data transfer item
             // (so that this drop zone knows what task was dropped)
             let data_transfer = DataTransfer::from_event(&ev);
-            let data: TaskTransfer = data_transfer.get_data();
 
             // assign task to human
-            Msg::Assign(data)
+            match data_transfer.get_data::<TaskTransfer>() {
+                Ok(Some(data)) => Msg::Assign(data),
+                Ok(None) => Msg::Nothing,
+                Err(e) => Msg::DropFailed(e),
+            }
+        });
+
+        // humans can also be dropped onto tasks and stages
+        let transfer = HumanTransfer {
+            id: self.props.id,
+            name: name.to_string(),
+        };
+        let dragstart_handler = self.link.callback(move |ev: DragEvent| {
+            let data_transfer = DataTransfer::from_event(&ev);
+            data_transfer.set_data(&transfer);
+            data_transfer.set_drop_effect(DragEffect::Link);
+            Msg::Nothing
+        });
+
+        let contextmenu_handler = self.link.callback(|ev: MouseEvent| {
+            let ev: &Event = ev.as_ref();
+            ev.prevent_default();
+            Msg::ToggleMenu
+        });
+        let keydown_handler = self.link.batch_callback(|ev: KeyboardEvent| {
+            match ev.key().as_str() {
+                "Enter" | "ContextMenu" => {}
+                "F10" if ev.shift_key() => {}
+                _ => return vec![],
+            }
+            let target = ev
+                .target()
+                .and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+            if target.map(|t| t.class_name().contains("human-outer")) != Some(true) {
+                // the key was pressed in the menu
+                return vec![];
+            }
+            let ev: &Event = ev.as_ref();
+            ev.prevent_default();
+            vec![Msg::ToggleMenu]
         });
 
         let status_class = format!("human-status-{}", self.props.status);
 
         let name_style = format!("border-color: {}", self.props.color);
 
-        let outer_classes = match (self.props.bring_up, self.props.id == 0) {
-            (false, false) => "human-outer",
-            (true, false) => "human-outer human-outer-up",
-            (false, true) => "human-outer you",
-            (true, true) => "human-outer human-outer-up you",
-        };
+        let outer_classes = classes!(
+            "human-outer",
+            self.props.bring_up.then(|| "human-outer-up"),
+            (self.props.id == 0).then(|| "you"),
+            match self.props.drop_hint {
+                None => None,
+                Some(Ok(())) => Some("human-outer-drop-valid"),
+                Some(Err(_)) => Some("human-outer-drop-invalid"),
+            },
+        );
 
         let status = match self.props.status {
             HumanStatus::Idle => "",
@@ -210,13 +353,31 @@ impl Component for Human {
             HumanStatus::Reviewing => "👀",
         };
 
+        let label = tr_vars(
+            "human.label",
+            &[
+                ("$NAME", &name),
+                (
+                    "$STATUS",
+                    &tr(&format!("human.status.{}", self.props.status)),
+                ),
+            ],
+        );
+
         // humans are drop zones: drop a task on it to assign it to this human
         html! {
-            <div class=outer_classes
+            <div class=outer_classes draggable="true" tabindex="0"
+                    ref=self.node.clone()
+                    role="listitem" aria-label=label data-human-id=self.props.id.to_string()
+                    aria-haspopup="menu" aria-expanded=self.menu_open.to_string()
+                    onblur=self.blur_handler()
+                    oncontextmenu=contextmenu_handler
+                    onkeydown=keydown_handler
+                    ondragstart=dragstart_handler
                     ondragenter=dragenter_handler
                     ondragover=dragover_handler
                     ondrop=drop_handler>
-                <div class=classes!("human-activity", status_class)>{status}</div>
+                <div class=classes!("human-activity", status_class) aria-hidden="true">{status}</div>
                 <div class="human-head">
                     <div class="human-eye">
                       <div class="human-eye-pupil" />
@@ -227,6 +388,73 @@ impl Component for Human {
                 </div>
                 <div class="human-body"></div>
                 <div class="human-name" style=name_style>{ name }</div>
+                {
+                    if self.menu_open {
+                        self.view_menu()
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+impl Human {
+    /// A handler closing the menu once focus leaves the human.
+    fn blur_handler(&self) -> Callback<FocusEvent> {
+        let node = self.node.clone();
+        self.link.batch_callback(move |ev: FocusEvent| {
+            let inside = ev
+                .related_target()
+                .and_then(|t| t.dyn_into::<web_sys::Node>().ok())
+                .zip(node.get())
+                .map(|(target, human)| human.contains(Some(&target)))
+                .unwrap_or(false);
+            if inside {
+                vec![]
+            } else {
+                vec![Msg::CloseMenu]
+            }
+        })
+    }
+
+    /// The menu of actions on the team:
+    /// letting this human go or bringing back someone who left.
+    fn view_menu(&self) -> Html {
+        // you cannot let yourself go
+        let lay_off_item = if self.props.id != 0 {
+            let label = tr_vars("human.lay_off", &[("$NAME", &self.props.name)]);
+            let click_handler = self.link.callback(|_| Msg::LayOff);
+            html! {
+                <button role="menuitem" tabindex="-1" onclick=click_handler onblur=self.blur_handler()>{label}</button>
+            }
+        } else {
+            html! {}
+        };
+        let rehire_items = self.props.alumni.iter().map(|(human_id, name)| {
+            let human_id = *human_id;
+            let label = tr_vars("human.rehire", &[("$NAME", name)]);
+            let click_handler = self.link.callback(move |_| Msg::Rehire(human_id));
+            html! {
+                <button role="menuitem" tabindex="-1" onclick=click_handler onblur=self.blur_handler()>{label}</button>
+            }
+        });
+
+        let node = self.node.clone();
+        let keydown_handler = self.link.batch_callback(move |ev: KeyboardEvent| {
+            if menu_keydown(&ev, &node) {
+                vec![Msg::CloseMenu]
+            } else {
+                vec![]
+            }
+        });
+
+        html! {
+            <div class="task-menu human-menu" role="menu" aria-label=tr("human.menu")
+                 ref=self.menu.clone() onkeydown=keydown_handler>
+                { lay_off_item }
+                { for rehire_items }
             </div>
         }
     }
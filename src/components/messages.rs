@@ -1,12 +1,23 @@
-use std::{borrow::Cow, collections::HashMap, rc::Rc};
+use std::{collections::HashMap, rc::Rc};
+
+use serde::{Deserialize, Serialize};
 
 use yew::{html, Html};
 
-use crate::state::{FullReport, MonthlyReport};
+use crate::{
+    analytics::Sample,
+    i18n::{tr, tr_count, tr_html, tr_vars},
+    keyboard,
+    saves::SaveStats,
+    state::{FullReport, MonthlyReport, TICKS_PER_MONTH},
+};
 
-use super::human::GameHuman;
+use super::{
+    charts,
+    human::{GameHuman, HumanStats},
+};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum Message {
     /// A simple message with only a title and message.
     Simple { title: String, message: String },
@@ -25,6 +36,18 @@ pub enum Message {
 
     /// Game end message, no way to continue.
     EndMessage(FullReport),
+
+    /// The share code of the current game
+    Share { code: String },
+
+    /// Charts of the game's metrics over time
+    Dashboard(Vec<Sample>),
+
+    /// The list of key bindings
+    Help,
+
+    /// Figures on the size of the saved game
+    Debug(SaveStats),
 }
 
 impl Message {
@@ -36,69 +59,34 @@ impl Message {
         }
     }
 
-    /// Create a new simple message with template variable replacements (e.g. `$PROJECT_NAME`).
+    /// Create a new simple message with template variable replacements (e.g. `$PRODUCT_NAME`)
+    /// in both title and message.
     pub fn new_simple_with_vars(
         title: impl Into<String>,
         message: impl Into<String>,
         vars: &HashMap<&'static str, String>,
     ) -> Self {
+        let mut title: String = title.into();
         let mut message: String = message.into();
 
         for (key, var_value) in vars {
+            title = title.replace(key, var_value);
             message = message.replace(key, var_value);
         }
 
-        Message::Simple {
-            title: title.into(),
-            message,
-        }
+        Message::Simple { title, message }
     }
 
-    pub fn random_report(report_id: u32) -> Self {
-        let title = "General message via chat".to_string();
-
-        let message = match report_id {
-            0 => "Today is pizza day! 🍕 Don't forget to mark your preference in the #lunch channel!",
-            1 => "Don't forget that next Wednesday is Meme day. Post your memes on the #memes channel.",
-            2 => "Hey folks, let's go grab some coffee! ☕",
-            3 => "I heard it's been a rough night for the DevOps team. I wonder why we still have a single DevOps team in the first place...",
-            4 => "Things have been complicated over at DevOps. Lend a hand if you can.",
-            5 => "Today's sprint meeting was postponed to Thursday after lunch.",
-            6 => "Meetings, meetings, meetings. Can't get no work done with all these meetings! 😒",
-            7 => "Big Company at it again, huh?",
-            8 => "Is CI OK? It's taking ages to build!",
-            _ => "Sorry, my mistake. There is no message for you.",
-        };
-        Message::new_simple(title, message)
-    }
-
-    pub fn bug_reported() -> Self {
-        let title = "A message from the board of directors".to_string();
-
-        let body = "Clients are complaining about a problem with the software. This is crippling our image. Please fix it as soon as possible.";
-        Message::new_simple(title, body)
-    }
-
-    pub fn extra_technical_debt() -> Self {
-        let title = "Emergency dev meeting report".to_string();
-        let body = "Team members have called out that one of the key dependencies is very outdated, and are having trouble working with this version. Consider placing more efforts in migrating dependencies.";
-
-        Message::new_simple(title, body)
-    }
-
-    pub fn feature_requested() -> Self {
-        let title = "A message from the board of directors".to_string();
-        let body = "Our favorite client has requested a feature. Please be sure to work on it in due time.";
+    pub fn new_human(human: &GameHuman) -> Self {
+        let title = tr("msg.board.title");
+        let body = tr_vars("msg.new_human", &[("$NAME", &human.name)]);
 
         Message::new_simple(title, body)
     }
 
-    pub fn new_human(human: &GameHuman) -> Self {
-        let title = "A message from the board of directors".to_string();
-        let body = format!(
-            "{} has been hired, and is now part of your development team!",
-            human.name
-        );
+    pub fn human_returned(human: &GameHuman) -> Self {
+        let title = tr("msg.board.title");
+        let body = tr_vars("msg.human_returned", &[("$NAME", &human.name)]);
 
         Message::new_simple(title, body)
     }
@@ -115,35 +103,89 @@ impl Message {
             Message::Ceo { product_name } => ceo(product_name),
             Message::CeoDecline => ceo_decline(),
             Message::EndMessage(report) => end_message(report),
-        }
-    }
-
-    pub fn human_quit(name: Cow<str>) -> Message {
-        Message::Simple {
-            title: format!("{} is leaving the team", name),
-            message: format!("You were just informed that {} could not keep up with the pressure and left the company.", name),
+            Message::Share { code } => share(code),
+            Message::Dashboard(samples) => dashboard(samples),
+            Message::Help => help(),
+            Message::Debug(stats) => debug(stats),
         }
     }
 }
 
 fn end_of_month(report: &MonthlyReport) -> Html {
     let complexity = match report.complexity {
-        0..=10 => "very low",
-        11..=20 => "low",
-        21..=50 => "manageable",
-        51..=69 => "high",
-        70..=96 => "very high",
-        _ => "unbearable",
+        0..=10 => "complexity.very_low",
+        11..=20 => "complexity.low",
+        21..=50 => "complexity.manageable",
+        51..=69 => "complexity.high",
+        70..=96 => "complexity.very_high",
+        _ => "complexity.unbearable",
     };
 
     html! {
-        <ul class="month-report">
-            <li><strong>{"Score gained: "}</strong><span>{report.score}</span></li>
-            <li><strong>{"Total score: "}</strong><span>{report.total_score}</span></li>
-            <li><strong>{"Tasks done: "}</strong><span>{report.tasks_done}</span></li>
-            <li><strong>{"Bugs fixed: "}</strong><span>{report.bugs_fixed}</span></li>
-            <li><strong>{"Technical debt: "}</strong><span>{complexity}</span></li>
-        </ul>
+        <>
+            <ul class="month-report">
+                <li><strong>{tr("report.score_gained")}</strong><span>{report.score}</span></li>
+                <li><strong>{tr("report.total_score")}</strong><span>{report.total_score}</span></li>
+                <li><strong>{tr("report.tasks_done")}</strong><span>{report.tasks_done}</span></li>
+                <li><strong>{tr("report.bugs_fixed")}</strong><span>{report.bugs_fixed}</span></li>
+                <li><strong>{tr("report.technical_debt")}</strong><span>{tr(complexity)}</span></li>
+                <li><strong>{tr("report.outdated_dependencies")}</strong><span>{report.outdated_dependencies}</span></li>
+                {report_days("report.lead_time", report.lead_time)}
+                {report_days("report.cycle_time", report.cycle_time)}
+                {report_days("report.review_time", report.review_time)}
+            </ul>
+            {team_report(&report.team)}
+        </>
+    }
+}
+
+/// A table of what each human did in the month.
+fn team_report(team: &[(String, HumanStats)]) -> Html {
+    let rows = team
+        .iter()
+        .map(|(name, stats)| {
+            let idle = stats.idle_ticks * 100 / TICKS_PER_MONTH;
+            html! {
+                <tr>
+                    <th>{name}</th>
+                    <td>{stats.tasks_developed}</td>
+                    <td>{stats.reviews_done}</td>
+                    <td>{stats.bugs_introduced}</td>
+                    <td>{stats.bugs_caught}</td>
+                    <td>{format!("{}%", idle)}</td>
+                    <td>{format!("+{}", stats.experience_gained)}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <table class="month-team">
+            <thead>
+                <tr>
+                    <th>{tr("report.team.name")}</th>
+                    <th>{tr("report.team.developed")}</th>
+                    <th>{tr("report.team.reviews")}</th>
+                    <th>{tr("report.team.bugs_introduced")}</th>
+                    <th>{tr("report.team.bugs_caught")}</th>
+                    <th>{tr("report.team.idle")}</th>
+                    <th>{tr("report.team.experience")}</th>
+                </tr>
+            </thead>
+            <tbody>
+                {rows}
+            </tbody>
+        </table>
+    }
+}
+
+/// A report line with an average duration in days, if available.
+fn report_days(id: &str, days: Option<f64>) -> Html {
+    match days {
+        Some(days) => html! {
+            <li><strong>{tr(id)}</strong><span>{tr_vars("report.days", &[("$N", &format!("{:.1}", days))])}</span></li>
+        },
+        None => html! {},
     }
 }
 
@@ -166,71 +208,56 @@ fn speaker_guy() -> Html {
     }
 }
 
+/// A paragraph of translated text with inline markup.
+fn p(id: &str) -> Html {
+    html! {
+        <p>{tr_html(id, &[])}</p>
+    }
+}
+
 fn tutorial(phase: u32) -> Html {
     let text_node = match phase {
         1 => html! {
             <>
                 { speaker_guy() }
                 <div class="modal-speaker-content">
-                    <p>
-                        {"Hey there! 👋 I'm Guy! I heard you are going to replace me as the next development lead next month. 🙂 "}
-                        {"I'll give you an overview of the code base"}
-                        {" and explain how to coordinate a team once you have more developers involved."}
-                    </p>
+                    { p("tutorial.1.p1") }
                 </div>
             </>
         },
         2 => html! {
             <>
+                { p("tutorial.2.p1") }
                 <p>
-                    {"Behind me is the workboard that the company is using "}
-                    {"to keep track of tasks and understand how much progress has been done in them."}
-                </p>
-                <p>
-                    {"There are five stages every task must go through, in this order:"}
+                    {tr("tutorial.2.p2")}
                     <ol>
-                        <li><b>{"Backlog: "}</b> {"all tasks are devised here"}</li>
-                        <li><b>{"Sprint candidate: "}</b> {"for tasks to be worked on soon"}</li>
-                        <li><b>{"In progress: "}</b> {"for tasks with development work in progress"}</li>
-                        <li><b>{"Under review: "}</b> {"to double check that the changes are in good condition"}</li>
-                        <li><b>{"Done: "}</b> {"when all contributions were merged upstream"}</li>
+                        <li>{tr_html("tutorial.2.backlog", &[])}</li>
+                        <li>{tr_html("tutorial.2.candidate", &[])}</li>
+                        <li>{tr_html("tutorial.2.progress", &[])}</li>
+                        <li>{tr_html("tutorial.2.review", &[])}</li>
+                        <li>{tr_html("tutorial.2.done", &[])}</li>
                     </ol>
                 </p>
             </>
         },
         3 => html! {
             <>
-                <p>
-                    {"Let's get our hands dirty. "}
-                    {"I just received a request for an easy, but definitely game-changing feature. "}
-                    {"This is a good first task for you!"}
-                </p>
-                <p>
-                    {"Here, let me file a ticket with the main idea real quick."}
-                </p>
+                { p("tutorial.3.p1") }
+                { p("tutorial.3.p2") }
             </>
         },
         4 => html! {
             <>
-                <p>
-                    {"Here it is. You will find the ticket with a unique ID in the Backlog. "}
-                    {"But note that this is a "}<em>{"stub"}</em>{". "}
-                    {"I mostly grabbed some quotes from the e-mail with the idea and wrote the use case story. "}
-                    {"Before we start working on it, we need to describe it in detail, "}
-                    {"nail down the requirements and enumerate acceptance criteria. "}
-                </p>
-                <p>
-                    {"I'll let you take care of this. To prepare the task, "}
-                    {"move it by dragging and dropping onto the next column, Sprint candidate."}
-                </p>
+                { p("tutorial.4.p1") }
+                { p("tutorial.4.p2") }
                 <div class="onboarding-wrapper">
                     <div class="board-stage board-stage-tutorial board-stage-backlog">
-                        <div class="board-stage-header">{"Backlog"}</div>
+                        <div class="board-stage-header">{tr("stage.backlog")}</div>
                         <div class="board-stage-body">
                         </div>
                     </div>
                     <div class="board-stage board-stage-tutorial board-stage-candidate">
-                        <div class="board-stage-header">{"Sprint Candidate"}</div>
+                        <div class="board-stage-header">{tr("stage.candidate")}</div>
                         <div class="board-stage-body"/>
                     </div>
                     <div class="board-task board-task-tutorial-4">
@@ -243,17 +270,9 @@ fn tutorial(phase: u32) -> Html {
         },
         5 => html! {
             <>
-                <p>{"Yup, that looks OK!"}</p>
-                <p>
-                    {"This would be the part where you delegate someone to work on it, "}
-                    {"by "}<em>{"assigning"}</em>{" the task to someone. "}
-                    {"However, this might not always be possible due to lack of staff. "}
-                    {"So this time, you'll be the one writing some code."}
-                </p>
-                <p>
-                    {"Assign this task to yourself by dragging and dropping onto your avatar. "}
-                    {"Then, you will be able to move it to the next stage, In progress."}
-                </p>
+                { p("tutorial.5.p1") }
+                { p("tutorial.5.p2") }
+                { p("tutorial.5.p3") }
                 <div class="onboarding-wrapper">
                     <div class="board-task board-task-tutorial-5">
                         {"T351"}
@@ -271,7 +290,7 @@ fn tutorial(phase: u32) -> Html {
                                 </div>
                             </div>
                             <div class="human-body"></div>
-                        <div class="human-name">{"You"}</div>
+                        <div class="human-name">{tr("human.you")}</div>
                     </div>
 
                 </div>
@@ -279,129 +298,63 @@ fn tutorial(phase: u32) -> Html {
         },
         6 => html! {
             <>
-                <p>{"Good work!"}</p>
-
-                <p>
-                    {"Once done, we can merge these changes or review them first. "}
-                    {"It might happen that you or your developers introduce bugs and whatnot. 😬"}
-                </p>
-                <p>
-                    {"Move the task to the next stage, Under review, "}
-                    {"and let it stay there for a while. "}
-                </p>
+                { p("tutorial.6.p1") }
+                { p("tutorial.6.p2") }
+                { p("tutorial.6.p3") }
                 <div class="message-meta">
-                  <h4>{"Meta tip:"}</h4>
-                  <p>
-                    {"If you feel that time is running slowly, "}
-                    {"use the speed buttons in the status bar. "}
-                    {"You can even pause the game there, "}
-                    {"for when you're under pressure!"}
-                  </p>
+                  <h4>{tr("tutorial.6.meta_title")}</h4>
+                  { p("tutorial.6.meta") }
                 </div>
             </>
         },
         7 => html! {
             <>
-                <p>{"You found a bug!"}</p>
-
-                <p>
-                    {"You're most likely to find bugs than not, so don't worry. "}
-                    {"There is still time to fix it. Move it back to In Progress and rework on it."}
-                </p>
-                <p>
-                    {"I mean, nothing stops you from delivering the feature with this bug, "}
-                    {"but I don't think you should be indifferent to it."}
-                </p>
+                { p("tutorial.7.p1") }
+                { p("tutorial.7.p2") }
+                { p("tutorial.7.p3") }
             </>
         },
         8 => html! {
             <>
-                <p>
-                    {"You can perform as many review iterations as you like. "}
-                    {"The more time you review, the higher the chances of finding more bugs!"}
-                </p>
-
-                <p>
-                    {"Ultimately though, you'll want to bring it upstream. "}
-                    {"Move the task to Done when you no longer intend to work on it."}
-                </p>
+                { p("tutorial.8.p1") }
+                { p("tutorial.8.p2") }
             </>
         },
         9 => html! {
             <>
-                <p>
-                    {"Each task has a score representing its overall impact on the product. "}
-                    {"Merge more of these tasks each month to increase your score!"}
-                </p>
-                <p>
-                    {"In your spare time, you should think of other things to work on the project "}
-                    {"and write them down immediately as stubs. "}
-                    {"When you're not pressured by deadlines, "}
-                    {"this will enable you to continue making value."}
-                </p>
+                { p("tutorial.9.p1") }
+                { p("tutorial.9.p2") }
             </>
         },
         10 => html! {
             <>
-                <p>
-                    {"Ah, you stumbled upon a bug while playing around with the software! "}
-                    {"Bug tasks are the right kind for that. "}
-                    {"They do not yield as many points, but will improve the quality and image towards our clients. "}
-                </p>
-                <p>
-                    {"Better us finding and fixing the bugs before they complain about them!"}
-                </p>
+                { p("tutorial.10.p1") }
+                { p("tutorial.10.p2") }
             </>
         },
         11 => html! {
             <>
-                <p>
-                    {"I will work alongside you as a developer for the rest of the month. "}
-                    {"You can either give me tasks to code for, or let me review your own code."}
-                </p>
-                <p>
-                    {"Peer review is generally better: since the writer of the code is a bit biased, "}
-                    {"it will be easier for other developers to discover certain bugs. "}
-                    {"You might not always have the opportunity to do peer review on each and every task, "}
-                    {"but I would strongly recommend it in the future!"}
-                </p>
+                { p("tutorial.11.p1") }
+                { p("tutorial.11.p2") }
             </>
         },
         12 => html! {
             <>
-                <p>
-                    {"You have just created a chore task. "}
-                    {"Chores to not contribute to your score, but they help to keep the code maintainable. "}
-                </p>
-                <p>
-                    {"Personally, I don't like working with messy code, and neither will your colleagues. "}
-                </p>
+                { p("tutorial.12.p1") }
+                { p("tutorial.12.p2") }
             </>
         },
         13 => html! {
             <>
                 <p>
-                    {"I don't have much time left in the team. "}
-                    {"Just a few tips before I go:"}
+                    {tr("tutorial.13.p1")}
                     <ul>
-                        <li>
-                            {"You can't work on task specification or task ingestion while also coding or reviewing. "}
-                            {"Delegate those to your team of developers as much as you can."}
-                        </li>
-                        <li>
-                            {"Some tasks may have a deadline imposed by the board. "}
-                            {"Deliver features on time, otherwise you'll get penalties."}
-                        </li>
-                        <li>
-                            {"QA is still important though. "}
-                            {"Working and merging fast on tasks will increase the complexity of the software "}
-                            {"and make future tasks harder to work on."}
-                        </li>
+                        <li>{tr_html("tutorial.13.tip1", &[])}</li>
+                        <li>{tr_html("tutorial.13.tip2", &[])}</li>
+                        <li>{tr_html("tutorial.13.tip3", &[])}</li>
                     </ul>
                 </p>
-                <p>
-                    {"I hope the onboarding was satisfying to you. I'll see you around. Best wishes! 👋"}
-                </p>
+                { p("tutorial.13.p2") }
             </>
         },
         _ => html! {},
@@ -434,22 +387,9 @@ fn ceo(product_name: &str) -> Html {
         <>
             { speaker_ceo() }
             <div class="modal-speaker-content">
-                <p>
-                    {"Warm greetings. I am most pleased to have seen your immense progress in "}{product_name}{" since you joined us. "}
-                    {"Your achievements are most impressive! "}
-                    {"I've been talking with the rest of the board about you, "}
-                    {"and they agree that you are just the kind we need to bring "}{product_name}
-                    {" to worlwide acclaim."}
-                </p>
-                <p>
-                    {"I will get straight to the chase. "}
-                    {"I want to promote you to Chief Product Engineer of the company. "}
-                    {"It comes with new products to oversee, a new salary, new office, more benefits, "}
-                    {"and you will be free to leave that workboard of yours to someone else."}
-                </p>
-                <p>
-                    {"So, do you accept?"}
-                </p>
+                <p>{tr_html("ceo.p1", &[("$PRODUCT_NAME", product_name)])}</p>
+                { p("ceo.p2") }
+                { p("ceo.p3") }
             </div>
         </>
     }
@@ -460,40 +400,109 @@ fn ceo_decline() -> Html {
         <>
             { speaker_ceo() }
             <div class="modal-speaker-content">
-                <p>
-                    {"Well, I must say I am disappointed, but it's your call. "}
-                    {"You really seem to like that workboard. 🤷 "}
-                </p>
+                { p("ceo.declined") }
             </div>
         </>
     }
 }
 
+fn share(code: &str) -> Html {
+    html! {
+        <>
+            { p("share.explanation") }
+            <textarea class="share-code" readonly=true value=code.to_string() />
+            { p("share.file") }
+        </>
+    }
+}
+
 fn end_message(report: &FullReport) -> Html {
+    let months = tr_count("end.months", report.months as i64);
+    let team = tr_count("end.team", report.team_size as i64);
+
     html! {
         <>
+            { p("end.p1") }
             <p>
-                {"Your decision is made. "}
-                {"You accept the offer and step up in your career, "}
-                {"away from the software project board. "}
-                {"A new wave of challenges await."}
-            </p>
-            <p>
-                <strong>{"Thank you for playing 10x Sprint Master!"}</strong>
+                <strong>{tr("end.thanks")}</strong>
             </p>
             <h3>{&report.product_name}</h3>
-            <p>
-                {"Complete in "}
-                <strong>{report.months} {" months"}</strong>
-                {" with "}
-                <strong>{report.team_size}{" team members"}</strong>
-                {"."}
-            </p>
+            <p>{tr_html("end.complete", &[("$MONTHS", &months), ("$TEAM", &team)])}</p>
             <ul class="month-report">
-                <li><strong>{"Total score: "}</strong><span>{report.total_score}</span></li>
-                <li><strong>{"Total tasks done: "}</strong><span>{report.tasks_done}</span></li>
-                <li><strong>{"Total number of bugs fixed: "}</strong><span>{report.bugs_fixed}</span></li>
+                <li><strong>{tr("end.total_score")}</strong><span>{report.total_score}</span></li>
+                <li><strong>{tr("end.total_tasks")}</strong><span>{report.tasks_done}</span></li>
+                <li><strong>{tr("end.total_bugs")}</strong><span>{report.bugs_fixed}</span></li>
+                {report_days("report.lead_time", report.lead_time)}
+                {report_days("report.cycle_time", report.cycle_time)}
             </ul>
         </>
     }
 }
+
+fn dashboard(samples: &[Sample]) -> Html {
+    if samples.len() < 2 {
+        return html! {
+            <p>{tr("dashboard.empty")}</p>
+        };
+    }
+
+    html! {
+        <div class="dashboard">
+            {charts::cumulative_flow(samples)}
+            {charts::burndown(samples)}
+            {charts::score(samples)}
+        </div>
+    }
+}
+
+fn debug(stats: &SaveStats) -> Html {
+    let storage_used = stats
+        .storage_used
+        .map(|bytes| bytes.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let rows = [
+        ("debug.save_size", stats.save_size.to_string()),
+        ("debug.storage_used", storage_used),
+        ("debug.tasks_on_board", stats.tasks_on_board.to_string()),
+        ("debug.archived_months", stats.archived_months.to_string()),
+        ("debug.archived_tasks", stats.archived_tasks.to_string()),
+        ("debug.log_entries", stats.log_entries.to_string()),
+        ("debug.samples", stats.samples.to_string()),
+    ]
+    .iter()
+    .map(|(id, value)| {
+        html! {
+            <tr>
+                <th>{tr(id)}</th>
+                <td>{value}</td>
+            </tr>
+        }
+    })
+    .collect::<Html>();
+
+    html! {
+        <table class="debug-stats">
+            {rows}
+        </table>
+    }
+}
+
+fn help() -> Html {
+    let rows = keyboard::BINDINGS
+        .iter()
+        .map(|(keys, id)| {
+            html! {
+                <tr>
+                    <th><kbd>{keys}</kbd></th>
+                    <td>{tr(id)}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <table class="key-bindings">
+            {rows}
+        </table>
+    }
+}
@@ -1,6 +1,7 @@
 use yew::prelude::*;
 
 pub mod board;
+pub mod charts;
 pub mod clock;
 pub mod game;
 pub mod human;
@@ -8,8 +9,11 @@ pub mod menu;
 pub mod messages;
 pub mod modal;
 pub mod pause;
+pub mod perf;
 pub mod stage;
 pub mod task;
+pub mod timeline;
+pub mod unassign;
 
 pub fn progress_bar(outer_class: &'static str, inner_class: &'static str, progress: f32) -> Html {
     let style = format!("width: {}%;", progress * 100.);
@@ -41,8 +41,8 @@ pub fn create_modal(title: &str, children: Children) -> Html {
     html! {
         <>
         <div class="modal-background" />
-        <div class="modal">
-            <h2>{title}</h2>
+        <div class="modal" role="dialog" aria-modal="true" aria-labelledby="modal-title">
+            <h2 id="modal-title">{title}</h2>
 
             <div class="modal-content">
                 {children}
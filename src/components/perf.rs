@@ -0,0 +1,107 @@
+//! Module for the overlay measuring how fast the game renders.
+//!
+//! The time is taken from sending a tick to the simulation
+//! until the next animation frame after the board was rendered
+//! with its outcome.
+//!
+
+use yew::prelude::*;
+
+use crate::i18n::tr;
+
+/// Measurements of tick-to-paint times, in milliseconds.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PaintStats {
+    /// the time of the last tick measured
+    pub last: f64,
+    /// the longest time measured
+    pub max: f64,
+    /// the sum of all times measured
+    pub total: f64,
+    /// the number of ticks measured
+    pub count: u32,
+    /// the number of tasks rendered again
+    /// after the last answer of the simulation
+    pub tasks_rendered: usize,
+}
+
+impl PaintStats {
+    /// Record the time taken by one more tick.
+    pub fn record(&mut self, ms: f64) {
+        self.last = ms;
+        self.max = self.max.max(ms);
+        self.total += ms;
+        self.count += 1;
+    }
+
+    /// The average time per tick.
+    pub fn average(&self) -> f64 {
+        if self.count == 0 {
+            0.
+        } else {
+            self.total / self.count as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    pub stats: PaintStats,
+}
+
+pub struct PerfOverlay {
+    props: Props,
+}
+
+impl Component for PerfOverlay {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        PerfOverlay { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let stats = &self.props.stats;
+        let ms = |value: f64| format!("{:.1}", value);
+        let rows = [
+            ("perf.last", ms(stats.last)),
+            ("perf.average", ms(stats.average())),
+            ("perf.max", ms(stats.max)),
+            ("perf.ticks", stats.count.to_string()),
+            ("perf.tasks_rendered", stats.tasks_rendered.to_string()),
+        ]
+        .iter()
+        .map(|(id, value)| {
+            html! {
+                <tr>
+                    <th>{tr(id)}</th>
+                    <td>{value}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+        html! {
+            <div class="perf-overlay" aria-hidden="true">
+                <div class="perf-overlay-title">{tr("perf.title")}</div>
+                <table>
+                    {rows}
+                </table>
+            </div>
+        }
+    }
+}
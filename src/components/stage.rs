@@ -2,8 +2,13 @@ use serde::{Deserialize, Serialize};
 use yew::{agent::Dispatcher, prelude::*};
 
 use crate::{
-    data_transfer::{payload::TaskTransfer, DataTransfer, DragEffect},
+    data_transfer::{
+        payload::{HumanTransfer, TaskTransfer},
+        DataTransfer, DragEffect,
+    },
     event_bus::{EventBus, EventBusRequest},
+    i18n::tr,
+    state::{GameError, MoveError},
 };
 
 #[derive(Debug, Clone, PartialEq, Properties)]
@@ -12,6 +17,10 @@ pub struct Props {
     pub id: StageId,
     /// A human readable description of the stage
     pub description: String,
+    /// Whether the task being dragged could be moved here,
+    /// or `None` if no task is being dragged onto other stages
+    #[prop_or_default]
+    pub drop_hint: Option<Result<(), MoveError>>,
     /*
     #[prop_or_default]
     pub children: ChildrenWithProps<super::task::Task>,
@@ -67,10 +76,12 @@ impl StageId {
 pub enum Msg {
     /// For when there is still a dragged task moving on the stage.
     TaskOver,
-    /// For when a dragged task entered the stage.
-    CheckTask(TaskTransfer),
     /// For when a dragged task was dropped onto the stage.
     DropTask(TaskTransfer),
+    /// For when a dragged human was dropped onto the stage.
+    DropHuman(HumanTransfer),
+    /// For when whatever was dropped onto the stage could not be read.
+    DropFailed(GameError),
 }
 
 impl Component for Stage {
@@ -88,7 +99,6 @@ impl Component for Stage {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::TaskOver => false,
-            Msg::CheckTask(_task_transfer) => false,
             Msg::DropTask(task_transfer) => {
                 // emit request to dispatcher
                 if task_transfer.from != self.props.id {
@@ -101,6 +111,18 @@ impl Component for Stage {
                     false
                 }
             }
+            Msg::DropHuman(human) => {
+                // reviewers pick up the oldest task waiting for review
+                if self.props.id == StageId::Review {
+                    self.event_bus
+                        .send(EventBusRequest::AssignReview { human_id: human.id });
+                }
+                false
+            }
+            Msg::DropFailed(e) => {
+                self.event_bus.send(EventBusRequest::ReportError(e));
+                false
+            }
         }
     }
 
@@ -122,13 +144,21 @@ impl Component for Stage {
             }
             Msg::TaskOver
         });
-        let dragover_handler = self.link.callback(|ev: DragEvent| {
-            {
+        let accepts_humans = self.props.id == StageId::Review;
+        let accepts_task = !matches!(self.props.drop_hint, Some(Err(_)));
+        let dragover_handler = self.link.callback(move |ev: DragEvent| {
+            let data_transfer = DataTransfer::from_event(&ev);
+            if data_transfer.has_data::<HumanTransfer>() {
+                if accepts_humans {
+                    let ev: &Event = ev.as_ref();
+                    ev.prevent_default();
+                    data_transfer.set_drop_effect(DragEffect::Link);
+                }
+            } else if accepts_task {
                 let ev: &Event = ev.as_ref();
                 ev.prevent_default();
+                data_transfer.set_drop_effect(DragEffect::Move);
             }
-            let data_transfer = DataTransfer::from_event(&ev);
-            data_transfer.set_drop_effect(DragEffect::Move);
             Msg::TaskOver
         });
         let drop_handler = self.link.callback(move |ev: DragEvent| {
@@ -140,16 +170,37 @@ impl Component for Stage {
             // (so that this drop zone knows what task was dropped)
             let data_transfer = DataTransfer::from_event(&ev);
 
-            let data: TaskTransfer = data_transfer.get_data();
-            Msg::DropTask(data)
+            let dropped = match data_transfer.get_data::<HumanTransfer>() {
+                Ok(Some(human)) => Ok(Msg::DropHuman(human)),
+                Ok(None) => data_transfer.get_data::<TaskTransfer>().and_then(|task| {
+                    task.map(Msg::DropTask)
+                        .ok_or_else(|| GameError::Transfer("nothing to drop".to_string()))
+                }),
+                Err(e) => Err(e),
+            };
+            dropped.unwrap_or_else(Msg::DropFailed)
         });
 
+        let (hint_class, hint) = match self.props.drop_hint {
+            None => (None, html! {}),
+            Some(Ok(())) => (Some("board-stage-drop-valid"), html! {}),
+            Some(Err(e)) => (
+                Some("board-stage-drop-invalid"),
+                html! {
+                    <div class="board-stage-drop-reason">{tr(e.message_id())}</div>
+                },
+            ),
+        };
+
         let e_id = format!("stage_{}", id);
+        let header_id = format!("stage_{}_header", id);
         html! {
             <div id=e_id
-                    class=classes!("board-stage", {format!("board-stage-{}", id)})
+                    class=classes!("board-stage", format!("board-stage-{}", id), hint_class)
+                    role="group" aria-labelledby=header_id.clone() data-stage=id
                     ondrop=drop_handler ondragover=dragover_handler ondragenter=dragenter_handler>
-                <div class="board-stage-header">{&self.props.description}</div>
+                <div id=header_id class="board-stage-header">{&self.props.description}</div>
+                {hint}
                 <div class="board-stage-body">
                     {for self.props.children.iter()}
                 </div>
@@ -1,13 +1,20 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, rc::Rc};
 
+use gloo_timers::callback::Timeout;
 use serde::{Deserialize, Serialize};
-use yew::{agent::Dispatcher, prelude::*};
+use wasm_bindgen::JsCast;
+use yew::{agent::Dispatcher, prelude::*, web_sys};
 
 use crate::{
     components::{bug, progress_bar},
-    data_transfer::{payload::TaskTransfer, DataTransfer, DragEffect},
+    data_transfer::{
+        payload::{HumanTransfer, TaskTransfer},
+        pointer::{self, DropTarget, PointerDrag, Press},
+        DataTransfer, DragEffect,
+    },
     event_bus::{EventBus, EventBusRequest},
-    state::Timestamp,
+    i18n::{tr, tr_vars},
+    state::{GameError, Timestamp, TICKS_PER_DAY},
 };
 
 use super::stage::StageId;
@@ -24,7 +31,8 @@ pub struct GameTask {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deadline: Option<Timestamp>,
-    /// a description of the task
+    /// the message ID of the task's description,
+    /// or the description itself
     #[serde(default)]
     #[serde(skip_serializing_if = "str::is_empty")]
     pub description: String,
@@ -36,7 +44,8 @@ pub struct GameTask {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub assigned: Option<u32>,
-    /// the ID of the user who completed the development of the task
+    /// the ID of the user who last worked on the development of the task,
+    /// which is the one who completed it once it is developed
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub developed_by: Option<u32>,
@@ -58,9 +67,40 @@ pub struct GameTask {
     pub bugs: u32,
     /// the number of bugs found through review
     pub bugs_found: u32,
-    /// Whether the task is visible
-    /// (old tasks already done are hidden in the subsequent month)
-    pub visible: bool,
+    /// the ID of the dependency which this task migrates to its latest version
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub migrates: Option<u32>,
+    /// the changes of stage and assignee since the task was created,
+    /// oldest first
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<TaskTransition>,
+}
+
+/// A change in the stage or assignee of a task.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TaskTransition {
+    /// the moment of the change
+    pub time: Timestamp,
+    /// the stage of the task from this moment
+    pub stage: StageId,
+    /// the ID of the user assigned to the task from this moment
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned: Option<u32>,
+}
+
+/// How long a task has spent on the board, in ticks.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct TaskTimings {
+    /// since the task was created until it was merged
+    pub lead: Timestamp,
+    /// since development started until the task was merged,
+    /// if development started at all
+    pub cycle: Option<Timestamp>,
+    /// under review
+    pub review: Timestamp,
 }
 
 /// Details for constructing a new task.
@@ -68,7 +108,8 @@ pub struct GameTask {
 /// Used by world state actually create a new task.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameTaskBuilder {
-    /// a description of the task
+    /// the message ID of the task's description,
+    /// or the description itself
     pub description: String,
     /// the kind of task
     pub kind: TaskKind,
@@ -81,6 +122,9 @@ pub struct GameTaskBuilder {
     pub difficulty: u32,
     /// number of ticks after which the task is due
     pub max_time: Option<u32>,
+    /// the ID of the dependency which this task migrates
+    #[serde(default)]
+    pub migrates: Option<u32>,
 }
 
 impl GameTaskBuilder {
@@ -97,6 +141,7 @@ impl GameTaskBuilder {
             score,
             difficulty,
             max_time: None,
+            migrates: None,
         }
     }
 
@@ -113,8 +158,15 @@ impl GameTaskBuilder {
             score,
             difficulty,
             max_time: Some(crate::state::TICKS_PER_MONTH),
+            migrates: None,
         }
     }
+
+    /// Make the task a migration of the given dependency.
+    pub fn migrating(mut self, dependency_id: u32) -> Self {
+        self.migrates = Some(dependency_id);
+        self
+    }
 }
 
 impl GameTask {
@@ -142,7 +194,8 @@ impl GameTask {
             specified: false,
             bugs: if kind == TaskKind::Bug { 1 } else { 0 },
             bugs_found: if kind == TaskKind::Bug { 1 } else { 0 },
-            visible: true,
+            migrates: None,
+            transitions: Vec::new(),
         }
     }
 
@@ -171,7 +224,8 @@ impl GameTask {
             specified: false,
             bugs: if kind == TaskKind::Bug { 1 } else { 0 },
             bugs_found: if kind == TaskKind::Bug { 1 } else { 0 },
-            visible: true,
+            migrates: None,
+            transitions: Vec::new(),
         }
     }
 
@@ -215,6 +269,68 @@ impl GameTask {
         self.stage = stage;
     }
 
+    /// Record the current stage and assignee of the task,
+    /// if either changed since the last transition.
+    ///
+    /// Changes within the same tick are collapsed into one transition.
+    pub fn record_transition(&mut self, time: Timestamp) {
+        let (stage, assigned) = self
+            .transitions
+            .last()
+            .map(|t| (t.stage, t.assigned))
+            .unwrap_or((StageId::Backlog, None));
+        if stage == self.stage && assigned == self.assigned {
+            return;
+        }
+        let transition = TaskTransition {
+            time,
+            stage: self.stage,
+            assigned: self.assigned,
+        };
+        match self.transitions.last_mut() {
+            Some(last) if last.time == time => *last = transition,
+            _ => self.transitions.push(transition),
+        }
+    }
+
+    /// The moment in which the task first entered the given stage.
+    pub fn entered(&self, stage: StageId) -> Option<Timestamp> {
+        self.transitions
+            .iter()
+            .find(|t| t.stage == stage)
+            .map(|t| t.time)
+    }
+
+    /// The total number of ticks that the task has spent in the given stage.
+    pub fn time_in_stage(&self, stage: StageId, now: Timestamp) -> Timestamp {
+        let mut total = 0;
+        let mut since = self.created;
+        let mut current = StageId::Backlog;
+        for t in &self.transitions {
+            if current == stage {
+                total += t.time.saturating_sub(since);
+            }
+            since = t.time;
+            current = t.stage;
+        }
+        if current == stage && current != StageId::Done {
+            total += now.saturating_sub(since);
+        }
+        total
+    }
+
+    /// How long the task has spent on the board so far.
+    pub fn timings(&self, now: Timestamp) -> TaskTimings {
+        let end = self.entered(StageId::Done).unwrap_or(now);
+        TaskTimings {
+            lead: end.saturating_sub(self.created),
+            cycle: self
+                .entered(StageId::Progress)
+                .map(|start| end.saturating_sub(start)),
+            review: self.time_in_stage(StageId::Review, now),
+        }
+    }
+
     /// Whether the requirements have already been fully specified.
     pub fn is_specified(&self) -> bool {
         self.specified
@@ -263,6 +379,16 @@ pub struct Props {
     pub bugs_found: u32,
     /// Portion of time left before reaching the deadline
     pub deadline_ratio: Option<f32>,
+    /// How long the task has spent on the board
+    #[prop_or_default]
+    pub timings: TaskTimings,
+    /// Whether the task is selected for keyboard control
+    #[prop_or(false)]
+    pub selected: bool,
+    /// The humans to which the task can be assigned (ID and name),
+    /// listed in its action menu
+    #[prop_or_default]
+    pub team: Rc<Vec<(u32, String)>>,
 }
 
 /// View component for a task (done or to be done).
@@ -279,6 +405,16 @@ pub struct Task {
     props: Props,
     link: ComponentLink<Self>,
     event_bus: Dispatcher<EventBus>,
+    /// whether the action menu is open
+    menu_open: bool,
+    /// the task's element
+    node: NodeRef,
+    /// the action menu's element
+    menu: NodeRef,
+    /// a press which will pick up the task if held long enough
+    press: Option<(Press, Timeout)>,
+    /// the task being dragged with a pointer
+    pointer_drag: Option<PointerDrag>,
 }
 
 /// An enumeration for the kind of task
@@ -311,6 +447,30 @@ pub enum Msg {
     Drag,
     /// The cursor is over the task.
     Hover,
+    /// The task received focus.
+    Focus,
+    /// Open the action menu, or close it if open.
+    ToggleMenu,
+    /// Close the action menu.
+    CloseMenu,
+    /// Move the task to another stage (from the action menu).
+    MoveTo(StageId),
+    /// Assign the task to a human (from the action menu).
+    AssignTo(u32),
+    /// Leave the task without anyone assigned (from the action menu).
+    Unassign,
+    /// A touch or pen pressed the task.
+    PointerDown(Press),
+    /// The pointer moved while pressing or dragging the task.
+    PointerMove(PointerEvent),
+    /// The task was pressed long enough to be picked up.
+    PickUp,
+    /// The pointer was released at this point of the viewport.
+    PointerUp(i32, i32),
+    /// The pointer was interrupted (e.g. by scrolling).
+    PointerCancel,
+    /// Whatever was dropped onto the task could not be read.
+    DropFailed(GameError),
 }
 
 impl Component for Task {
@@ -322,6 +482,11 @@ impl Component for Task {
             props,
             link,
             event_bus: EventBus::dispatcher(),
+            menu_open: false,
+            node: NodeRef::default(),
+            menu: NodeRef::default(),
+            press: None,
+            pointer_drag: None,
         }
     }
 
@@ -337,11 +502,114 @@ impl Component for Task {
                 self.event_bus
                     .send(EventBusRequest::DragTaskEnd(self.props.id));
             }
+            Msg::Focus => {
+                self.event_bus
+                    .send(EventBusRequest::SelectTask(self.props.id));
+            }
+            // a long press on touch screens also asks for the context menu
+            Msg::ToggleMenu if self.pointer_drag.is_none() => {
+                self.menu_open = !self.menu_open;
+                return true;
+            }
+            Msg::CloseMenu if self.menu_open => {
+                self.menu_open = false;
+                return true;
+            }
+            Msg::MoveTo(to) => {
+                self.menu_open = false;
+                let task = self.transfer();
+                self.event_bus.send(EventBusRequest::MoveTask { task, to });
+                return true;
+            }
+            Msg::AssignTo(human_id) => {
+                self.menu_open = false;
+                let task = self.transfer();
+                self.event_bus
+                    .send(EventBusRequest::AssignTask { task, human_id });
+                return true;
+            }
+            Msg::Unassign => {
+                self.menu_open = false;
+                let task = self.transfer();
+                self.event_bus.send(EventBusRequest::UnassignTask { task });
+                return true;
+            }
+            Msg::DropFailed(e) => {
+                self.event_bus.send(EventBusRequest::ReportError(e));
+                return false;
+            }
+            Msg::PointerDown(press) => {
+                let link = self.link.clone();
+                let timeout = Timeout::new(pointer::LONG_PRESS_MS, move || {
+                    link.send_message(Msg::PickUp)
+                });
+                self.press = Some((press, timeout));
+            }
+            Msg::PointerMove(ev) => {
+                if let Some(drag) = &self.pointer_drag {
+                    drag.move_to(ev.client_x(), ev.client_y());
+                } else if let Some((press, _)) = &self.press {
+                    if press.moved_away(&ev) {
+                        // not a long press, drop the timeout
+                        self.press = None;
+                    }
+                }
+            }
+            Msg::PickUp => {
+                let source = self.node.cast::<web_sys::Element>();
+                if let (Some((press, _)), Some(source)) = (self.press.take(), source) {
+                    match PointerDrag::start(&source, press) {
+                        Ok(drag) => {
+                            self.pointer_drag = Some(drag);
+                            self.menu_open = false;
+                            self.event_bus
+                                .send(EventBusRequest::DragTaskStart(self.props.id));
+                            return true;
+                        }
+                        Err(e) => {
+                            gloo_console::error!("Could not pick up task:", e);
+                        }
+                    }
+                }
+            }
+            Msg::PointerUp(x, y) => {
+                self.press = None;
+                if self.pointer_drag.take().is_some() {
+                    self.event_bus
+                        .send(EventBusRequest::DragTaskEnd(self.props.id));
+                    let task = self.transfer();
+                    if let Some(request) = DropTarget::at(x, y).and_then(|t| t.request(task)) {
+                        self.event_bus.send(request);
+                    }
+                    return true;
+                }
+            }
+            Msg::PointerCancel => {
+                self.press = None;
+                if self.pointer_drag.take().is_some() {
+                    self.event_bus
+                        .send(EventBusRequest::DragTaskEnd(self.props.id));
+                    return true;
+                }
+            }
             _ => {}
         }
         false
     }
 
+    fn rendered(&mut self, _first_render: bool) {
+        // bring focus into the menu once opened
+        if self.menu_open {
+            let menu_has_focus = yew::utils::document()
+                .active_element()
+                .and_then(|active| active.closest("[role=menu]").ok().flatten())
+                .is_some();
+            if !menu_has_focus {
+                focus_first_item(&self.menu);
+            }
+        }
+    }
+
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
         if self.props != props {
             self.props = props;
@@ -366,6 +634,12 @@ impl Component for Task {
         if self.props.bugs_found > 0 {
             classes.push("board-task-with-bug");
         }
+        if self.props.selected {
+            classes.push("board-task-selected");
+        }
+        if self.pointer_drag.is_some() {
+            classes.push("board-task-lifted");
+        }
         if let Some(deadline_ratio) = self.props.deadline_ratio {
             classes.push("board-task-important");
 
@@ -400,6 +674,31 @@ impl Component for Task {
 
         let dragend_handler = self.link.callback(move |_ev: DragEvent| Msg::EndDrag);
 
+        // tasks are drop zones for humans: drop a human on it to assign it to them
+        let dragover_handler = self.link.callback(|ev: DragEvent| {
+            let data_transfer = DataTransfer::from_event(&ev);
+            if data_transfer.has_data::<HumanTransfer>() {
+                let ev: &Event = ev.as_ref();
+                ev.prevent_default();
+                ev.stop_propagation();
+                data_transfer.set_drop_effect(DragEffect::Link);
+            }
+            Msg::Hover
+        });
+        let drop_handler = self.link.batch_callback(|ev: DragEvent| {
+            let data_transfer = DataTransfer::from_event(&ev);
+            let dropped = match data_transfer.get_data::<HumanTransfer>() {
+                Ok(Some(human)) => Msg::AssignTo(human.id),
+                Err(e) => Msg::DropFailed(e),
+                // let the stage handle it
+                Ok(None) => return vec![],
+            };
+            let ev: &Event = ev.as_ref();
+            ev.prevent_default();
+            ev.stop_propagation();
+            vec![dropped]
+        });
+
         let drag_handler = self.link.callback(|ev: DragEvent| {
             let ev: &Event = ev.as_ref();
             ev.prevent_default();
@@ -417,17 +716,79 @@ impl Component for Task {
             Msg::Hover
         });
 
+        let focus_handler = self.link.callback(|_: FocusEvent| Msg::Focus);
+
+        let contextmenu_handler = self.link.callback(|ev: MouseEvent| {
+            let ev: &Event = ev.as_ref();
+            ev.prevent_default();
+            Msg::ToggleMenu
+        });
+        let keydown_handler = self.link.batch_callback(|ev: KeyboardEvent| {
+            match ev.key().as_str() {
+                "Enter" | "ContextMenu" => {}
+                "F10" if ev.shift_key() => {}
+                _ => return vec![],
+            }
+            let target = ev
+                .target()
+                .and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+            if target.map(|t| t.class_name().contains("board-task")) != Some(true) {
+                // the key was pressed in the menu
+                return vec![];
+            }
+            let ev: &Event = ev.as_ref();
+            ev.prevent_default();
+            vec![Msg::ToggleMenu]
+        });
+
+        // dragging with touch and pens
+        let pointerdown_handler = self.link.batch_callback(|ev: PointerEvent| {
+            if pointer::is_handled(&ev) && ev.is_primary() {
+                vec![Msg::PointerDown(Press::from_event(&ev))]
+            } else {
+                vec![]
+            }
+        });
+        let pointermove_handler = self.link.batch_callback(|ev: PointerEvent| {
+            if pointer::is_handled(&ev) {
+                vec![Msg::PointerMove(ev)]
+            } else {
+                vec![]
+            }
+        });
+        let pointerup_handler = self.link.batch_callback(|ev: PointerEvent| {
+            if pointer::is_handled(&ev) {
+                vec![Msg::PointerUp(ev.client_x(), ev.client_y())]
+            } else {
+                vec![]
+            }
+        });
+        let pointercancel_handler = self.link.callback(|_: PointerEvent| Msg::PointerCancel);
+
         let e_id = format!("task_T{}", self.props.id);
-        let description = Some(Cow::Owned(self.props.description.clone()));
+        let description = Some(Cow::Owned(self.tooltip()));
 
         let task_score_class = "board-task-score";
         html! {
-            <div id=e_id class=classes style=extra_style draggable="true" title=description
+            <div id=e_id class=classes style=extra_style draggable="true" title=description tabindex="0"
+                 ref=self.node.clone()
+                 role="button" aria-haspopup="menu" aria-expanded=self.menu_open.to_string()
+                 aria-label=self.label()
+                 onfocus=focus_handler
+                 onblur=self.blur_handler()
+                 oncontextmenu=contextmenu_handler
+                 onkeydown=keydown_handler
                  ondrag=drag_handler
                  ondragstart=dragstart_handler
                  ondragend=dragend_handler
+                 ondragover=dragover_handler
+                 ondrop=drop_handler
                  onmouseenter=mouseenter_handler
-                 onmouseleave=mouseleave_handler>
+                 onmouseleave=mouseleave_handler
+                 onpointerdown=pointerdown_handler
+                 onpointermove=pointermove_handler
+                 onpointerup=pointerup_handler
+                 onpointercancel=pointercancel_handler>
                 {
                     if self.props.bugs_found > 0 {
                         bug()
@@ -438,7 +799,7 @@ impl Component for Task {
                 {"T"}{self.props.id}
                 {
                     if let Some((_user_id, user_name, color_code)) = &self.props.assigned {
-                        let tooltip = format!("Assigned to {}", user_name);
+                        let tooltip = tr_vars("task.assigned_to", &[("$NAME", user_name)]);
                         let style = format!("background-color: {}", color_code);
                         html! {
                             <div class="board-task-assigned" title=tooltip.clone()>
@@ -458,7 +819,202 @@ impl Component for Task {
                         html! {}
                     }
                 }
+                {
+                    if self.menu_open {
+                        self.view_menu()
+                    } else {
+                        html! {}
+                    }
+                }
             </div>
         }
     }
 }
+
+impl Task {
+    /// The task as if it were being dragged.
+    fn transfer(&self) -> TaskTransfer {
+        TaskTransfer {
+            id: self.props.id,
+            kind: self.props.kind,
+            from: self.props.stage,
+            progress: self.props.progress,
+        }
+    }
+
+    /// A description of the task for assistive technologies.
+    fn label(&self) -> String {
+        let kind = match self.props.kind {
+            TaskKind::Normal => "task.kind.normal",
+            TaskKind::Bug => "task.kind.bug",
+            TaskKind::Chore => "task.kind.chore",
+        };
+        let id = format!("T{}", self.props.id);
+        let mut parts = vec![
+            id,
+            tr(kind),
+            tr(&format!("stage.{}", self.props.stage.to_str())),
+        ];
+        if self.props.kind != TaskKind::Chore {
+            parts.push(tr_vars(
+                "task.score",
+                &[("$SCORE", &self.props.score.to_string())],
+            ));
+        }
+        if self.props.progress > 0. && self.props.stage != StageId::Done {
+            let progress = format!("{:.0}", self.props.progress * 100.);
+            parts.push(tr_vars("task.progress", &[("$N", &progress)]));
+        }
+        if let Some((_, name, _)) = &self.props.assigned {
+            parts.push(tr_vars("task.assigned_to", &[("$NAME", name)]));
+        }
+        if self.props.bugs_found > 0 {
+            parts.push(tr("task.has_bugs"));
+        }
+        if self.props.deadline_ratio.is_some() {
+            parts.push(tr("task.extraordinary"));
+        }
+        parts.join(", ")
+    }
+
+    /// A handler closing the menu once focus leaves the task.
+    fn blur_handler(&self) -> Callback<FocusEvent> {
+        let node = self.node.clone();
+        self.link.batch_callback(move |ev: FocusEvent| {
+            let inside = ev
+                .related_target()
+                .and_then(|t| t.dyn_into::<web_sys::Node>().ok())
+                .zip(node.get())
+                .map(|(target, task)| task.contains(Some(&target)))
+                .unwrap_or(false);
+            if inside {
+                vec![]
+            } else {
+                vec![Msg::CloseMenu]
+            }
+        })
+    }
+
+    /// The menu of actions on this task,
+    /// an alternative to dragging it around.
+    fn view_menu(&self) -> Html {
+        const STAGES: [StageId; 5] = [
+            StageId::Backlog,
+            StageId::Candidate,
+            StageId::Progress,
+            StageId::Review,
+            StageId::Done,
+        ];
+
+        let move_items = STAGES
+            .iter()
+            .filter(|stage| **stage != self.props.stage)
+            .map(|stage| {
+                let stage = *stage;
+                let label = tr_vars(
+                    "task.move_to",
+                    &[("$STAGE", &tr(&format!("stage.{}", stage.to_str())))],
+                );
+                let click_handler = self.link.callback(move |_| Msg::MoveTo(stage));
+                html! {
+                    <button role="menuitem" tabindex="-1" onclick=click_handler onblur=self.blur_handler()>{label}</button>
+                }
+            });
+        let assign_items = self.props.team.iter().map(|(human_id, name)| {
+            let human_id = *human_id;
+            let label = tr_vars("task.assign_to", &[("$NAME", name)]);
+            let click_handler = self.link.callback(move |_| Msg::AssignTo(human_id));
+            html! {
+                <button role="menuitem" tabindex="-1" onclick=click_handler onblur=self.blur_handler()>{label}</button>
+            }
+        });
+
+        let unassign_item = if self.props.assigned.is_some() {
+            let click_handler = self.link.callback(|_| Msg::Unassign);
+            html! {
+                <button role="menuitem" tabindex="-1" onclick=click_handler onblur=self.blur_handler()>{tr("task.unassign")}</button>
+            }
+        } else {
+            html! {}
+        };
+
+        let node = self.node.clone();
+        let keydown_handler = self.link.batch_callback(move |ev: KeyboardEvent| {
+            if menu_keydown(&ev, &node) {
+                vec![Msg::CloseMenu]
+            } else {
+                vec![]
+            }
+        });
+
+        html! {
+            <div class="task-menu" role="menu" aria-label=tr("task.menu")
+                 ref=self.menu.clone() onkeydown=keydown_handler>
+                { for move_items }
+                { for assign_items }
+                { unassign_item }
+            </div>
+        }
+    }
+
+    /// The task's description followed by its timings.
+    fn tooltip(&self) -> String {
+        let days = |ticks: Timestamp| format!("{:.1}", ticks as f64 / TICKS_PER_DAY as f64);
+        let timings = &self.props.timings;
+        let mut lines = vec![];
+        if !self.props.description.is_empty() {
+            lines.push(self.props.description.clone());
+        }
+        lines.push(tr_vars("task.lead_time", &[("$N", &days(timings.lead))]));
+        if let Some(cycle) = timings.cycle {
+            lines.push(tr_vars("task.cycle_time", &[("$N", &days(cycle))]));
+        }
+        if timings.review > 0 {
+            lines.push(tr_vars(
+                "task.review_time",
+                &[("$N", &days(timings.review))],
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Move focus across the items of an action menu with the arrow keys.
+///
+/// Returns whether the menu should close (on Escape),
+/// in which case focus goes back to the element which owns the menu.
+pub(crate) fn menu_keydown(ev: &KeyboardEvent, owner: &NodeRef) -> bool {
+    let current = ev
+        .target()
+        .and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+    let next = match (ev.key().as_str(), current) {
+        ("Escape", _) => {
+            let ev: &Event = ev.as_ref();
+            ev.prevent_default();
+            if let Some(owner) = owner.cast::<web_sys::HtmlElement>() {
+                owner.focus().unwrap_or_default();
+            }
+            return true;
+        }
+        ("ArrowDown", Some(current)) => current.next_element_sibling(),
+        ("ArrowUp", Some(current)) => current.previous_element_sibling(),
+        _ => return false,
+    };
+    let ev: &Event = ev.as_ref();
+    ev.prevent_default();
+    if let Some(next) = next.and_then(|e| e.dyn_into::<web_sys::HtmlElement>().ok()) {
+        next.focus().unwrap_or_default();
+    }
+    false
+}
+
+/// Give focus to the first item of a menu.
+pub(crate) fn focus_first_item(menu: &NodeRef) {
+    let item = menu
+        .cast::<web_sys::Element>()
+        .and_then(|menu| menu.first_element_child())
+        .and_then(|item| item.dyn_into::<web_sys::HtmlElement>().ok());
+    if let Some(item) = item {
+        item.focus().unwrap_or_default();
+    }
+}
@@ -0,0 +1,155 @@
+use yew::prelude::*;
+
+use crate::event_log::{LogCategory, LogEntry};
+use crate::i18n::{tr, tr_count};
+
+/// The maximum number of entries shown at once.
+const MAX_SHOWN: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    /// the game log, oldest first
+    pub entries: Vec<LogEntry>,
+}
+
+pub enum Msg {
+    /// show or hide the log entries
+    ToggleExpanded,
+    /// only show entries of this category, or all if none
+    SetFilter(Option<LogCategory>),
+}
+
+pub struct Timeline {
+    props: Props,
+    link: ComponentLink<Self>,
+    expanded: bool,
+    filter: Option<LogCategory>,
+}
+
+impl Component for Timeline {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Timeline {
+            props,
+            link,
+            expanded: false,
+            filter: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::ToggleExpanded => {
+                self.expanded = !self.expanded;
+                true
+            }
+            Msg::SetFilter(filter) => {
+                self.filter = filter;
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let toggle_handler = self.link.callback(|_| Msg::ToggleExpanded);
+        let toggle_icon = if self.expanded { "▾" } else { "▸" };
+
+        html! {
+            <div class="timeline">
+                <div class="timeline-header">
+                    <button class="timeline-toggle" onclick=toggle_handler>
+                        {toggle_icon}{" "}{tr("log.title")}
+                    </button>
+                    {
+                        if self.expanded {
+                            self.view_filter()
+                        } else {
+                            html! {}
+                        }
+                    }
+                </div>
+                {
+                    if self.expanded {
+                        self.view_entries()
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+impl Timeline {
+    fn view_filter(&self) -> Html {
+        let filter_handler = self.link.callback(|ev: ChangeData| match ev {
+            ChangeData::Select(select) => Msg::SetFilter(LogCategory::from_code(&select.value())),
+            _ => Msg::SetFilter(None),
+        });
+        let options = LogCategory::ALL
+            .iter()
+            .map(|category| {
+                html! {
+                    <option value=category.code() selected={self.filter == Some(*category)}>
+                        {tr(&format!("log.category.{}", category.code()))}
+                    </option>
+                }
+            })
+            .collect::<Html>();
+
+        html! {
+            <select class="timeline-filter" onchange=filter_handler>
+                <option value="" selected={self.filter.is_none()}>{tr("log.category.all")}</option>
+                {options}
+            </select>
+        }
+    }
+
+    fn view_entries(&self) -> Html {
+        let entries = self
+            .props
+            .entries
+            .iter()
+            .rev()
+            .filter(|e| self.filter.map(|f| e.category == f).unwrap_or(true))
+            .take(MAX_SHOWN)
+            .map(|entry| {
+                let class = format!("timeline-entry timeline-{}", entry.category.code());
+                let when = format!(
+                    "{} · {}",
+                    tr_count("status.month", entry.month as i64),
+                    tr_count("log.day", entry.day() as i64)
+                );
+                html! {
+                    <li class=class>
+                        <span class="timeline-time">{when}</span>
+                        <span class="timeline-text">{entry.text()}</span>
+                    </li>
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if entries.is_empty() {
+            html! {
+                <p class="timeline-empty">{tr("log.empty")}</p>
+            }
+        } else {
+            html! {
+                <ul class="timeline-entries">
+                    {entries}
+                </ul>
+            }
+        }
+    }
+}
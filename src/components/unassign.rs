@@ -0,0 +1,115 @@
+use yew::{agent::Dispatcher, prelude::*};
+
+use crate::{
+    data_transfer::{payload::TaskTransfer, DataTransfer, DragEffect},
+    event_bus::{EventBus, EventBusRequest},
+    i18n::tr,
+    state::{GameError, MoveError},
+};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    /// Whether the task being dragged could be unassigned,
+    /// or `None` if no task is being dragged
+    #[prop_or_default]
+    pub drop_hint: Option<Result<(), MoveError>>,
+}
+
+/// Drop zone for leaving a task without anyone assigned,
+/// shown next to the humans while a task is being dragged.
+pub struct Unassign {
+    props: Props,
+    link: ComponentLink<Self>,
+    event_bus: Dispatcher<EventBus>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Msg {
+    Nothing,
+    Unassign(TaskTransfer),
+    DropFailed(GameError),
+}
+
+impl Component for Unassign {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Unassign {
+            props,
+            link,
+            event_bus: EventBus::dispatcher(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Nothing => false,
+            Msg::Unassign(task) => {
+                self.event_bus.send(EventBusRequest::UnassignTask { task });
+                false
+            }
+            Msg::DropFailed(e) => {
+                self.event_bus.send(EventBusRequest::ReportError(e));
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props != props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let accepts_task = !matches!(self.props.drop_hint, Some(Err(_)));
+        let dragenter_handler = self.link.callback(move |ev: DragEvent| {
+            {
+                let ev: &Event = ev.as_ref();
+                ev.prevent_default();
+            }
+            Msg::Nothing
+        });
+        let dragover_handler = self.link.callback(move |ev: DragEvent| {
+            if accepts_task {
+                let ev: &Event = ev.as_ref();
+                ev.prevent_default();
+                let data_transfer = DataTransfer::from_event(&ev);
+                data_transfer.set_drop_effect(DragEffect::Move);
+            }
+            Msg::Nothing
+        });
+        let drop_handler = self.link.callback(move |ev: DragEvent| {
+            {
+                let ev: &Event = ev.as_ref();
+                ev.prevent_default();
+            }
+            let data_transfer = DataTransfer::from_event(&ev);
+            match data_transfer.get_data::<TaskTransfer>() {
+                Ok(Some(task)) => Msg::Unassign(task),
+                Ok(None) => Msg::Nothing,
+                Err(e) => Msg::DropFailed(e),
+            }
+        });
+
+        let hint_class = match self.props.drop_hint {
+            None => None,
+            Some(Ok(())) => Some("unassign-drop-valid"),
+            Some(Err(_)) => Some("unassign-drop-invalid"),
+        };
+
+        html! {
+            <div class=classes!("unassign", hint_class) role="listitem" data-unassign=""
+                    ondragenter=dragenter_handler
+                    ondragover=dragover_handler
+                    ondrop=drop_handler>
+                <div class="unassign-icon" aria-hidden="true">{"🚫"}</div>
+                <div class="unassign-label">{tr("hr.unassign")}</div>
+            </div>
+        }
+    }
+}
@@ -4,12 +4,15 @@
 //!
 
 pub mod payload;
+pub mod pointer;
 
 use js_sys::{Array, Function, Object, Reflect};
-use serde::{de::DeserializeOwned, Serialize};
 use serde_qs;
 use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
 
+use self::payload::Payload;
+use crate::state::GameError;
+
 /// A data transfer object
 /// that contains the data being transferred to a drop zone.
 #[derive(Debug, Clone)]
@@ -25,22 +28,48 @@ impl DataTransfer {
         }
     }
 
+    /// Set the payload of the transfer.
     pub fn set_data<T>(&self, content: &T)
     where
-        T: Serialize,
+        T: Payload,
     {
         let payload = serde_qs::to_string(content)
             .map_err(|e| JsValue::from_str(&e.to_string()))
             .expect_throw("could not serialize data");
-        self.set_text_data("application/x-www-form-urlencoded", &payload)
+        self.set_text_data(T::MIME_TYPE, &payload)
+    }
+
+    /// Get the payload of the transfer,
+    /// or `None` if it does not contain one of this kind.
+    ///
+    /// Fails if the payload is there but cannot be read,
+    /// such as when it was made by another page.
+    pub fn get_data<T>(&self) -> Result<Option<T>, GameError>
+    where
+        T: Payload,
+    {
+        let str_data = self.get_text_data(T::MIME_TYPE)?;
+        if str_data.is_empty() {
+            return Ok(None);
+        }
+        serde_qs::from_str(&str_data)
+            .map(Some)
+            .map_err(|e| GameError::Transfer(e.to_string()))
     }
 
-    pub fn get_data<T>(&self) -> T
+    /// Whether the transfer contains a payload of this kind.
+    ///
+    /// Unlike the payload itself,
+    /// this is also available while dragging over a drop zone.
+    pub fn has_data<T>(&self) -> bool
     where
-        T: DeserializeOwned,
+        T: Payload,
     {
-        let str_data = self.get_text_data("application/x-www-form-urlencoded");
-        serde_qs::from_str(&str_data).expect_throw("could not deserialize data")
+        Reflect::get(&self.value, &"types".into())
+            .ok()
+            .and_then(|types| types.dyn_into::<Array>().ok())
+            .map(|types| types.includes(&JsValue::from_str(T::MIME_TYPE), 0))
+            .unwrap_or(false)
     }
 
     pub fn set_text_data(&self, mime_type: &str, content: &str) {
@@ -54,16 +83,18 @@ impl DataTransfer {
             .expect_throw("could not set data transfer");
     }
 
-    pub fn get_text_data(&self, mime_type: &str) -> String {
-        let fn_get_data: JsValue = Reflect::get(&self.value, &"getData".into()).unwrap_throw();
-        let fn_get_data: Function = fn_get_data.dyn_into().unwrap_throw();
+    pub fn get_text_data(&self, mime_type: &str) -> Result<String, GameError> {
+        let fn_get_data: Function = Reflect::get(&self.value, &"getData".into())
+            .ok()
+            .and_then(|f| f.dyn_into().ok())
+            .ok_or_else(|| GameError::Transfer("no data to get".to_string()))?;
         let args = Array::new();
-        args.set(0, JsValue::from_str(&mime_type));
+        args.set(0, JsValue::from_str(mime_type));
 
         Reflect::apply(&fn_get_data, &self.value, &args)
-            .expect_throw("could not get data transfer")
+            .map_err(|e| GameError::Transfer(describe(&e)))?
             .as_string()
-            .unwrap_throw()
+            .ok_or_else(|| GameError::Transfer("data is not text".to_string()))
     }
 
     /// Set the intended drag effect
@@ -75,16 +106,18 @@ impl DataTransfer {
     }
 
     /// Retrieve the current drag effect
-    pub fn drop_effect(&self) -> DragEffect {
-        let obj: &Object = self.value.dyn_ref().unwrap_throw();
-
-        let value =
-            Reflect::get(&obj, &"dropEffect".into()).expect_throw("could not get drop effect");
-
-        DragEffect::try_from(value).expect_throw("could not get drop effect")
+    pub fn drop_effect(&self) -> Result<DragEffect, GameError> {
+        Reflect::get(&self.value, &"dropEffect".into())
+            .and_then(DragEffect::try_from)
+            .map_err(|e| GameError::Transfer(describe(&e)))
     }
 }
 
+/// Describe an error thrown by the Web API.
+fn describe(error: &JsValue) -> String {
+    error.as_string().unwrap_or_else(|| format!("{:?}", error))
+}
+
 /// A drag and drop effect from the Drag and Drop Web API.
 ///
 /// See [`dropEffect` on MDN](https://developer.mozilla.org/en-US/docs/Web/API/DataTransfer/dropEffect)
@@ -1,7 +1,16 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::components::{stage::StageId, task::TaskKind};
 
+/// Data which can be transferred to a drop zone.
+///
+/// Each kind of payload is set under its own media type,
+/// so that drop zones can tell which one they received.
+pub trait Payload: Serialize + DeserializeOwned {
+    /// the media type of the payload in the data transfer
+    const MIME_TYPE: &'static str;
+}
+
 /// The data transferred when dragging a task to a drop zone.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct TaskTransfer {
@@ -12,3 +21,19 @@ pub struct TaskTransfer {
     pub from: StageId,
     pub progress: f64,
 }
+
+impl Payload for TaskTransfer {
+    const MIME_TYPE: &'static str = "application/x-10x-task";
+}
+
+/// The data transferred when dragging a human to a drop zone.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HumanTransfer {
+    /// the unique user ID (0 is You)
+    pub id: u32,
+    pub name: String,
+}
+
+impl Payload for HumanTransfer {
+    const MIME_TYPE: &'static str = "application/x-10x-human";
+}
@@ -0,0 +1,165 @@
+//! Drag and drop through pointer events,
+//! for devices on which the Drag and Drop API does not fire
+//! (most touch screens).
+//!
+//! A task is picked up with a long press,
+//! a ghost copy of it follows the pointer,
+//! and the drop zone under the pointer is found on release.
+//! The outcome is the same event bus request
+//! that the drop zone would have produced with the Drag and Drop API.
+//!
+
+use wasm_bindgen::{JsCast, JsValue};
+use yew::{
+    utils::document,
+    web_sys::{Element, HtmlElement, PointerEvent},
+};
+
+use super::payload::TaskTransfer;
+use crate::{components::stage::StageId, event_bus::EventBusRequest};
+
+/// The number of milliseconds that a task must be pressed
+/// before it is picked up.
+pub const LONG_PRESS_MS: u32 = 350;
+
+/// The distance in pixels that the pointer may travel during a long press
+/// before it is no longer considered one.
+const PRESS_TOLERANCE: i32 = 10;
+
+/// Whether the pointer is handled by this module,
+/// as mouse pointers use the Drag and Drop API instead.
+pub fn is_handled(ev: &PointerEvent) -> bool {
+    ev.pointer_type() != "mouse"
+}
+
+/// A press on a task which may become a drag.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Press {
+    pub pointer_id: i32,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Press {
+    pub fn from_event(ev: &PointerEvent) -> Self {
+        Press {
+            pointer_id: ev.pointer_id(),
+            x: ev.client_x(),
+            y: ev.client_y(),
+        }
+    }
+
+    /// Whether the pointer moved too far for this to be a long press.
+    pub fn moved_away(&self, ev: &PointerEvent) -> bool {
+        (ev.client_x() - self.x).abs() > PRESS_TOLERANCE
+            || (ev.client_y() - self.y).abs() > PRESS_TOLERANCE
+    }
+}
+
+/// A zone onto which a task can be dropped.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DropTarget {
+    /// a stage (column) of the board
+    Stage(StageId),
+    /// a human, by ID
+    Human(u32),
+    /// the zone for leaving tasks without anyone assigned
+    Unassign,
+}
+
+impl DropTarget {
+    /// Find the drop zone at the given point of the viewport.
+    ///
+    /// Stages are marked with a `data-stage` attribute,
+    /// humans with a `data-human-id` attribute
+    /// and the unassign zone with a `data-unassign` attribute.
+    pub fn at(x: i32, y: i32) -> Option<Self> {
+        let element = document().element_from_point(x as f32, y as f32)?;
+        if let Ok(Some(_)) = element.closest("[data-unassign]") {
+            return Some(DropTarget::Unassign);
+        }
+        if let Some(human) = element.closest("[data-human-id]").ok().flatten() {
+            let id = human.get_attribute("data-human-id")?.parse().ok()?;
+            return Some(DropTarget::Human(id));
+        }
+        let stage = element.closest("[data-stage]").ok().flatten()?;
+        let stage = match stage.get_attribute("data-stage")?.as_str() {
+            "backlog" => StageId::Backlog,
+            "candidate" => StageId::Candidate,
+            "progress" => StageId::Progress,
+            "review" => StageId::Review,
+            "done" => StageId::Done,
+            _ => return None,
+        };
+        Some(DropTarget::Stage(stage))
+    }
+
+    /// The request for dropping the given task here,
+    /// as the drop zone would have sent it.
+    pub fn request(self, task: TaskTransfer) -> Option<EventBusRequest> {
+        match self {
+            DropTarget::Stage(to) if task.from != to => {
+                Some(EventBusRequest::MoveTask { task, to })
+            }
+            DropTarget::Stage(_) => None,
+            DropTarget::Human(human_id) => Some(EventBusRequest::AssignTask { task, human_id }),
+            DropTarget::Unassign => Some(EventBusRequest::UnassignTask { task }),
+        }
+    }
+}
+
+/// A task being dragged with a pointer.
+///
+/// The ghost copy of the task is removed when this is dropped.
+#[derive(Debug)]
+pub struct PointerDrag {
+    ghost: HtmlElement,
+    offset_x: i32,
+    offset_y: i32,
+}
+
+impl PointerDrag {
+    /// Pick up the given element,
+    /// capturing the pointer of the press so that it keeps receiving its events.
+    pub fn start(source: &Element, press: Press) -> Result<Self, JsValue> {
+        source.set_pointer_capture(press.pointer_id)?;
+
+        let rect = source.get_bounding_client_rect();
+        let ghost: HtmlElement = source.clone_node_with_deep(true)?.dyn_into()?;
+        ghost.remove_attribute("id")?;
+        ghost.set_attribute("aria-hidden", "true")?;
+        ghost.class_list().add_1("board-task-ghost")?;
+        ghost
+            .style()
+            .set_property("width", &format!("{}px", rect.width()))?;
+        document()
+            .body()
+            .ok_or_else(|| JsValue::from_str("no document body"))?
+            .append_child(&ghost)?;
+
+        let drag = PointerDrag {
+            ghost,
+            offset_x: press.x - rect.left() as i32,
+            offset_y: press.y - rect.top() as i32,
+        };
+        drag.move_to(press.x, press.y);
+        Ok(drag)
+    }
+
+    /// Make the ghost follow the pointer.
+    pub fn move_to(&self, x: i32, y: i32) {
+        let style = self.ghost.style();
+        style
+            .set_property("left", &format!("{}px", x - self.offset_x))
+            .and_then(|_| style.set_property("top", &format!("{}px", y - self.offset_y)))
+            .unwrap_or_else(|e| {
+                gloo_console::error!("Could not move dragged task:", e);
+            });
+    }
+}
+
+impl Drop for PointerDrag {
+    fn drop(&mut self) {
+        self.ghost.remove();
+    }
+}
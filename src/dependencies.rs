@@ -0,0 +1,87 @@
+//! Module for the product's software dependencies.
+//!
+//! Dependencies release new major versions upstream over time.
+//! The longer they stay outdated, the more likely the team
+//! runs into trouble with them, which adds technical debt.
+//! Merging a migration chore brings a dependency up to date.
+//!
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Timestamp, TICKS_PER_MONTH};
+
+/// A software dependency of the product.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Dependency {
+    /// unique ID
+    pub id: u32,
+    /// the name of the package
+    pub name: Cow<'static, str>,
+    /// the major version currently in use
+    pub version: u32,
+    /// the latest major version released upstream
+    pub latest: u32,
+    /// timestamp of the moment that the version in use became outdated
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outdated_since: Option<Timestamp>,
+}
+
+impl Dependency {
+    /// Create a new up-to-date dependency.
+    pub fn new(id: u32, name: impl Into<Cow<'static, str>>, version: u32) -> Self {
+        Dependency {
+            id,
+            name: name.into(),
+            version,
+            latest: version,
+            outdated_since: None,
+        }
+    }
+
+    /// The number of major versions which the dependency in use
+    /// is behind the latest one.
+    pub fn versions_behind(&self) -> u32 {
+        self.latest - self.version
+    }
+
+    /// The number of ticks since the dependency in use became outdated.
+    pub fn age(&self, now: Timestamp) -> u32 {
+        self.outdated_since
+            .map(|since| now.saturating_sub(since))
+            .unwrap_or(0)
+    }
+
+    /// The number of full months since the dependency in use became outdated.
+    pub fn age_in_months(&self, now: Timestamp) -> u32 {
+        self.age(now) / TICKS_PER_MONTH
+    }
+
+    /// Signal that a new major version was released upstream.
+    pub fn release(&mut self, now: Timestamp) {
+        self.latest += 1;
+        if self.outdated_since.is_none() {
+            self.outdated_since = Some(now);
+        }
+    }
+
+    /// Migrate the product to the latest version of the dependency.
+    pub fn migrate(&mut self) {
+        self.version = self.latest;
+        self.outdated_since = None;
+    }
+}
+
+/// The dependencies of a brand new product.
+pub fn initial_dependencies() -> Vec<Dependency> {
+    vec![
+        Dependency::new(0, "webframe", 3),
+        Dependency::new(1, "jsonkit", 1),
+        Dependency::new(2, "ormly", 5),
+        Dependency::new(3, "authz", 2),
+        Dependency::new(4, "chartist", 7),
+        Dependency::new(5, "logwise", 4),
+    ]
+}
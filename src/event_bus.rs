@@ -11,6 +11,7 @@ use yew::worker::{Agent, AgentLink, Context, HandlerId};
 use crate::{
     components::{stage::StageId, task::GameTaskBuilder},
     data_transfer::payload::TaskTransfer,
+    state::GameError,
 };
 
 /// All messages that can be sent through the event bus.
@@ -21,17 +22,36 @@ pub enum EventBusRequest {
     MoveTask { task: TaskTransfer, to: StageId },
     /// Assign the task to a human
     AssignTask { task: TaskTransfer, human_id: u32 },
+    /// Leave the task without anyone assigned
+    UnassignTask { task: TaskTransfer },
+    /// Assign the human to review the oldest task under review
+    /// which nobody else is reviewing
+    AssignReview { human_id: u32 },
+    /// Let a human go from the team
+    LayOff { human_id: u32 },
+    /// Bring a human who left back into the team
+    Rehire { human_id: u32 },
+    /// Report an error which happened outside of the game state,
+    /// such as unreadable data dropped onto the board
+    ReportError(GameError),
     /// Signal that a task is being dragged
     DragTaskStart(u32),
     /// Signal that the task stopped being dragged
     DragTaskEnd(u32),
+    /// Signal that the task was selected (e.g. it received focus)
+    SelectTask(u32),
     /// Add the given task details to the board.
     /// Usually employed by tutorial.
     AddTask(GameTaskBuilder),
-    /// Advance the tutorial to the next phase
+    /// Acknowledge the current tutorial message,
+    /// which may advance the tutorial
     AdvanceTutorial,
     /// An in-game time tick occurred.
     Tick,
+    /// Undo the last board action of the player
+    Undo,
+    /// Redo the last undone board action
+    Redo,
     /// Advance the the next month,
     /// usually as a consequence of pressing OK on the End of Month modal.
     NextMonth,
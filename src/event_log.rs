@@ -0,0 +1,120 @@
+//! Module for the log of things which happened in the game.
+//!
+//! Entries keep a message ID and its template variables rather than text,
+//! so that the log is presented in the current locale.
+//!
+
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::tr_vars, state::Timestamp};
+
+/// The maximum number of entries kept in the log,
+/// older entries are dropped.
+pub const MAX_LOG_ENTRIES: usize = 200;
+
+/// The number of working days in a month, for presentation.
+const DAYS_PER_MONTH: u32 = 20;
+
+/// The kind of thing which happened.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogCategory {
+    /// tasks added, moved or merged
+    Task,
+    /// bugs introduced, found or shipped
+    Bug,
+    /// score gained or lost
+    Score,
+    /// humans joining or leaving the team
+    Team,
+    /// major events
+    Event,
+}
+
+impl LogCategory {
+    /// All categories.
+    pub const ALL: [LogCategory; 5] = [
+        LogCategory::Task,
+        LogCategory::Bug,
+        LogCategory::Score,
+        LogCategory::Team,
+        LogCategory::Event,
+    ];
+
+    /// A short name for the category,
+    /// also used as a message ID suffix.
+    pub fn code(self) -> &'static str {
+        match self {
+            LogCategory::Task => "task",
+            LogCategory::Bug => "bug",
+            LogCategory::Score => "score",
+            LogCategory::Team => "team",
+            LogCategory::Event => "event",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        LogCategory::ALL.iter().copied().find(|c| c.code() == code)
+    }
+}
+
+/// An entry in the game's log.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LogEntry {
+    /// the month in which it happened
+    pub month: u32,
+    /// the number of ticks since the start of the month
+    pub time: Timestamp,
+    pub category: LogCategory,
+    /// the message ID of the entry's text
+    pub id: String,
+    /// template variables of the text (e.g. `$TASK`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub vars: Vec<(String, String)>,
+}
+
+impl LogEntry {
+    pub fn new(
+        month: u32,
+        time: Timestamp,
+        category: LogCategory,
+        id: &str,
+        vars: &[(&str, String)],
+    ) -> Self {
+        LogEntry {
+            month,
+            time,
+            category,
+            id: id.to_string(),
+            vars: vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        }
+    }
+
+    /// The working day of the month in which it happened, starting at 1.
+    pub fn day(&self) -> u32 {
+        self.time * DAYS_PER_MONTH / crate::state::TICKS_PER_MONTH + 1
+    }
+
+    /// The entry's text in the current locale.
+    pub fn text(&self) -> String {
+        let vars: Vec<(&str, &str)> = self
+            .vars
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        tr_vars(&self.id, &vars)
+    }
+}
+
+/// Add an entry to the log, dropping the oldest entries if full.
+pub fn push_entry(log: &mut Vec<LogEntry>, entry: LogEntry) {
+    log.push(entry);
+    if log.len() > MAX_LOG_ENTRIES {
+        let excess = log.len() - MAX_LOG_ENTRIES;
+        log.drain(..excess);
+    }
+}
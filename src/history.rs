@@ -0,0 +1,157 @@
+//! Module for undoing and redoing board actions.
+//!
+//! Only the player's own moves and assignments are recorded,
+//! and only for a short while:
+//! the rest of the game moves on, so going back too far would not make sense.
+//! Undoing an action also reverts what it counted in the team's stats,
+//! while moves which advanced the tutorial are not recorded at all.
+//!
+
+use crate::{components::stage::StageId, state::Timestamp};
+
+/// The number of ticks during which a board action can be undone.
+pub const UNDO_WINDOW: Timestamp = 60;
+
+/// The maximum number of actions kept for undoing.
+const MAX_ACTIONS: usize = 32;
+
+/// A board action performed by the player.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BoardAction {
+    /// a task was moved to another stage
+    MoveTask {
+        task_id: u32,
+        from: StageId,
+        to: StageId,
+        /// the progress of the task before it was moved
+        progress: f64,
+        /// the human credited with a review by the move, if any
+        reviewer: Option<u32>,
+    },
+    /// a task was assigned to a human, or unassigned
+    AssignTask {
+        task_id: u32,
+        stage: StageId,
+        /// who was assigned to the task before
+        previous: Option<u32>,
+        human_id: Option<u32>,
+        /// the progress of the task before it was handed over
+        progress: f64,
+    },
+}
+
+/// The undo and redo stacks of board actions.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct History {
+    undo: Vec<(Timestamp, BoardAction)>,
+    redo: Vec<(Timestamp, BoardAction)>,
+}
+
+impl History {
+    /// Record an action which was just performed.
+    pub fn record(&mut self, time: Timestamp, action: BoardAction) {
+        self.undo.push((time, action));
+        if self.undo.len() > MAX_ACTIONS {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Forget the actions which were undone,
+    /// as the player did something else.
+    pub fn clear_redo(&mut self) {
+        self.redo.clear();
+    }
+
+    /// Forget the actions which are too old to undo or redo.
+    pub fn expire(&mut self, now: Timestamp) {
+        let recent = |(time, _): &(Timestamp, BoardAction)| now < time + UNDO_WINDOW;
+        self.undo.retain(recent);
+        self.redo.retain(recent);
+    }
+
+    /// Take the last action to undo.
+    pub fn pop_undo(&mut self) -> Option<(Timestamp, BoardAction)> {
+        self.undo.pop()
+    }
+
+    /// Take the last undone action to redo.
+    pub fn pop_redo(&mut self) -> Option<(Timestamp, BoardAction)> {
+        self.redo.pop()
+    }
+
+    /// Keep an undone action for redoing.
+    pub fn push_redo(&mut self, time: Timestamp, action: BoardAction) {
+        self.redo.push((time, action));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn move_task(task_id: u32) -> BoardAction {
+        BoardAction::MoveTask {
+            task_id,
+            from: StageId::Review,
+            to: StageId::Progress,
+            progress: 0.5,
+            reviewer: Some(1),
+        }
+    }
+
+    #[test]
+    fn undo_then_redo_gives_back_the_same_action() {
+        let mut history = History::default();
+        history.record(10, move_task(1));
+        history.record(12, move_task(2));
+
+        let (time, action) = history.pop_undo().unwrap();
+        assert_eq!((time, action), (12, move_task(2)));
+        history.push_redo(time, action);
+
+        assert_eq!(history.pop_redo(), Some((12, move_task(2))));
+        assert_eq!(history.pop_redo(), None);
+        assert_eq!(history.pop_undo(), Some((10, move_task(1))));
+        assert_eq!(history.pop_undo(), None);
+    }
+
+    #[test]
+    fn new_actions_forget_undone_ones() {
+        let mut history = History::default();
+        history.record(10, move_task(1));
+        let (time, action) = history.pop_undo().unwrap();
+        history.push_redo(time, action);
+
+        history.clear_redo();
+        history.record(11, move_task(2));
+        assert_eq!(history.pop_redo(), None);
+        assert_eq!(history.pop_undo(), Some((11, move_task(2))));
+    }
+
+    #[test]
+    fn old_actions_expire() {
+        let mut history = History::default();
+        history.record(10, move_task(1));
+        history.record(20, move_task(2));
+        history.push_redo(5, move_task(3));
+
+        history.expire(10 + UNDO_WINDOW);
+        assert_eq!(history.pop_redo(), None);
+        assert_eq!(history.pop_undo(), Some((20, move_task(2))));
+        assert_eq!(history.pop_undo(), None);
+    }
+
+    #[test]
+    fn only_the_latest_actions_are_kept() {
+        let mut history = History::default();
+        for i in 0..(MAX_ACTIONS as u32 + 5) {
+            history.record(i, move_task(i));
+        }
+        let mut count = 0;
+        while let Some((time, _)) = history.pop_undo() {
+            assert!(time >= 5);
+            count += 1;
+        }
+        assert_eq!(count, MAX_ACTIONS);
+    }
+}
@@ -0,0 +1,242 @@
+//! Module for localization of in-game text.
+//!
+//! All text is looked up by message ID
+//! in a bundle of messages for the current locale,
+//! see `assets/i18n/`.
+//! Messages missing in a bundle fall back to English,
+//! and unknown IDs are presented as is.
+//!
+
+use std::{cell::RefCell, collections::HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
+use yew::{utils::document, virtual_dom::VNode, Html};
+
+use crate::state::try_local_storage;
+
+/// A language supported by the game.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq, Deserialize, Serialize)]
+pub enum Locale {
+    /// English
+    #[serde(rename = "en")]
+    En,
+    /// Portuguese
+    #[serde(rename = "pt")]
+    Pt,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::En
+    }
+}
+
+impl Locale {
+    /// All supported locales.
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Pt];
+
+    /// The locale's language code
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Pt => "pt",
+        }
+    }
+
+    /// The name of the language in that language
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Pt => "Português",
+        }
+    }
+
+    /// Find the locale for the given language code
+    /// (e.g. `"pt"` or `"pt-PT"`).
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let language = code.split('-').next().unwrap_or(code);
+        Locale::ALL
+            .iter()
+            .copied()
+            .find(|l| l.code().eq_ignore_ascii_case(language))
+    }
+
+    fn bundle_source(self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../assets/i18n/en.json"),
+            Locale::Pt => include_str!("../assets/i18n/pt.json"),
+        }
+    }
+
+    /// The plural category of the given number in this language.
+    fn plural_category(self, n: i64) -> PluralCategory {
+        match (self, n) {
+            (_, 0) => PluralCategory::Zero,
+            (_, 1 | -1) => PluralCategory::One,
+            _ => PluralCategory::Other,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum PluralCategory {
+    Zero,
+    One,
+    Other,
+}
+
+/// A message in a bundle.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    /// plain text
+    Text(String),
+    /// text which depends on a count (`$N`)
+    Plural {
+        /// used when the count is 0,
+        /// falls back to `other`
+        #[serde(default)]
+        zero: Option<String>,
+        one: String,
+        other: String,
+    },
+}
+
+impl Entry {
+    fn text(&self, locale: Locale, n: Option<i64>) -> &str {
+        match self {
+            Entry::Text(text) => text,
+            Entry::Plural { zero, one, other } => match n.map(|n| locale.plural_category(n)) {
+                Some(PluralCategory::Zero) => zero.as_deref().unwrap_or(other),
+                Some(PluralCategory::One) => one,
+                _ => other,
+            },
+        }
+    }
+}
+
+/// All messages of a locale.
+#[derive(Debug)]
+struct Bundle {
+    locale: Locale,
+    messages: HashMap<String, Entry>,
+}
+
+impl Bundle {
+    fn load(locale: Locale) -> Self {
+        let messages = serde_json::from_str(locale.bundle_source())
+            .expect_throw("bundled locale messages are invalid");
+        Bundle { locale, messages }
+    }
+}
+
+thread_local! {
+    static FALLBACK: Bundle = Bundle::load(Locale::En);
+    static CURRENT: RefCell<Option<Bundle>> = RefCell::new(None);
+}
+
+/// Look up a message and format it.
+fn lookup(id: &str, n: Option<i64>, vars: &[(&str, &str)]) -> String {
+    let found = CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .and_then(|b| b.messages.get(id).map(|e| e.text(b.locale, n).to_string()))
+    });
+    let mut text = found
+        .or_else(|| FALLBACK.with(|b| b.messages.get(id).map(|e| e.text(b.locale, n).to_string())))
+        .unwrap_or_else(|| id.to_string());
+
+    if let Some(n) = n {
+        text = text.replace("$N", &n.to_string());
+    }
+    for (key, value) in vars {
+        text = text.replace(key, value);
+    }
+    text
+}
+
+/// Translate the message with the given ID.
+pub fn tr(id: &str) -> String {
+    lookup(id, None, &[])
+}
+
+/// Translate the message with the given ID,
+/// replacing template variables (e.g. `$NAME`).
+pub fn tr_vars(id: &str, vars: &[(&str, &str)]) -> String {
+    lookup(id, None, vars)
+}
+
+/// Translate the message with the given ID
+/// in the plural form for the given count,
+/// which replaces `$N`.
+pub fn tr_count(id: &str, n: i64) -> String {
+    lookup(id, Some(n), &[])
+}
+
+/// Translate a message with inline markup (e.g. `<em>`)
+/// into a rendered node.
+/// Variable values are escaped.
+pub fn tr_html(id: &str, vars: &[(&str, &str)]) -> Html {
+    let escaped: Vec<(&str, String)> = vars
+        .iter()
+        .map(|(key, value)| (*key, escape_html(value)))
+        .collect();
+    let escaped: Vec<(&str, &str)> = escaped.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    let text = lookup(id, None, &escaped);
+
+    let span = document().create_element("span").unwrap_throw();
+    span.set_inner_html(&text);
+    VNode::VRef(span.into())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The locale currently in use.
+pub fn current_locale() -> Locale {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map(|b| b.locale)
+            .unwrap_or_default()
+    })
+}
+
+/// Change the locale in use for the time being.
+pub fn use_locale(locale: Locale) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(Bundle::load(locale)));
+}
+
+/// Change the locale in use and remember it in local storage.
+pub fn set_locale(locale: Locale) -> Result<(), JsValue> {
+    use_locale(locale);
+
+    let local_storage = try_local_storage()?;
+    local_storage.set_item("locale", locale.code())?;
+    Ok(())
+}
+
+/// Load the locale saved in local storage,
+/// or the browser's preferred language if none was chosen yet.
+pub fn init_locale() {
+    let saved = try_local_storage()
+        .and_then(|s| s.get_item("locale"))
+        .unwrap_or_else(|e| {
+            gloo_console::error!("Could not load language settings:", e);
+            None
+        });
+
+    let locale = saved
+        .or_else(|| web_sys::window().and_then(|w| w.navigator().language()))
+        .and_then(|code| Locale::from_code(&code))
+        .unwrap_or_default();
+
+    CURRENT.with(|current| *current.borrow_mut() = Some(Bundle::load(locale)));
+}
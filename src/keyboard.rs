@@ -0,0 +1,120 @@
+//! Module for the keyboard controls of the game.
+//!
+//! Keys are translated into commands here,
+//! the game component decides what each command does.
+//!
+
+use wasm_bindgen::JsCast;
+use yew::{web_sys::Element, KeyboardEvent};
+
+use crate::components::stage::StageId;
+
+/// A command issued through the keyboard.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Command {
+    /// select the task above the selected one
+    SelectUp,
+    /// select the task below the selected one
+    SelectDown,
+    /// select a task in the stage to the left
+    SelectLeft,
+    /// select a task in the stage to the right
+    SelectRight,
+    /// clear the selection
+    Deselect,
+    /// move the selected task to the given stage
+    MoveTo(StageId),
+    /// assign the selected task to the human at this position in the team
+    AssignTo(usize),
+    /// leave the selected task without anyone assigned
+    Unassign,
+    /// either pause or set speed to normal
+    ToggleSpeed,
+    /// increase the game speed
+    Faster,
+    /// decrease the game speed, eventually pausing
+    Slower,
+    /// undo the last board action
+    Undo,
+    /// redo the last undone board action
+    Redo,
+    /// show the list of key bindings
+    Help,
+    /// show how much the game takes in storage
+    Debug,
+    /// show or hide how fast the board renders
+    PerfOverlay,
+}
+
+/// The key bindings presented to the player,
+/// as pairs of key labels and message IDs of their descriptions.
+pub const BINDINGS: [(&str, &str); 13] = [
+    ("↑ ↓ ← →", "keys.select"),
+    ("Esc", "keys.deselect"),
+    ("B C P R D", "keys.move"),
+    ("1 … 9, 0", "keys.assign"),
+    ("U", "keys.unassign"),
+    ("Space", "keys.pause"),
+    ("+ −", "keys.speed"),
+    ("Ctrl+Z", "keys.undo"),
+    ("Ctrl+Y", "keys.redo"),
+    ("?", "keys.help"),
+    ("Tab", "keys.focus"),
+    ("`", "keys.debug"),
+    ("~", "keys.perf"),
+];
+
+/// Translate a key press into a command, if it is bound to any.
+///
+/// Keys typed into form fields or menus are left alone.
+pub fn command_of(e: &KeyboardEvent) -> Option<Command> {
+    let handled_by_target = e
+        .target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .map(|element| {
+            matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+                || matches!(element.closest("[role=menu]"), Ok(Some(_)))
+        })
+        .unwrap_or(false);
+    if handled_by_target {
+        return None;
+    }
+
+    let key = e.key();
+    if e.ctrl_key() || e.meta_key() {
+        return match key.as_str() {
+            "z" => Some(Command::Undo),
+            "Z" | "y" | "Y" => Some(Command::Redo),
+            _ => None,
+        };
+    }
+    if e.alt_key() {
+        return None;
+    }
+
+    let command = match key.as_str() {
+        "ArrowUp" => Command::SelectUp,
+        "ArrowDown" => Command::SelectDown,
+        "ArrowLeft" => Command::SelectLeft,
+        "ArrowRight" => Command::SelectRight,
+        "Escape" => Command::Deselect,
+        "b" | "B" => Command::MoveTo(StageId::Backlog),
+        "c" | "C" => Command::MoveTo(StageId::Candidate),
+        "p" | "P" => Command::MoveTo(StageId::Progress),
+        "r" | "R" => Command::MoveTo(StageId::Review),
+        "d" | "D" => Command::MoveTo(StageId::Done),
+        "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" => {
+            Command::AssignTo(key.parse::<usize>().ok()? - 1)
+        }
+        "0" => Command::AssignTo(9),
+        "u" | "U" => Command::Unassign,
+        " " => Command::ToggleSpeed,
+        "+" | "=" => Command::Faster,
+        "-" | "_" => Command::Slower,
+        "?" => Command::Help,
+        "`" => Command::Debug,
+        "~" => Command::PerfOverlay,
+        _ => return None,
+    };
+    Some(command)
+}
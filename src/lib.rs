@@ -0,0 +1,606 @@
+#![allow(dead_code)]
+use i18n::{tr, tr_count, Locale};
+use migrations::SaveError;
+use saves::SlotInfo;
+use services::{ClockSettings, BASE_MILLISECONDS_PER_TICK, MAX_CATCH_UP_TICKS_CHOICES};
+use state::WorldState;
+use std::rc::Rc;
+use wasm_bindgen::UnwrapThrowExt;
+use web_sys::HtmlAudioElement;
+use yew::prelude::*;
+use yew::services::reader::{File, FileData, ReaderService, ReaderTask};
+
+mod analytics;
+mod archive;
+mod audio;
+mod catalog;
+mod changes;
+mod components;
+mod dependencies;
+mod event_log;
+mod history;
+mod i18n;
+mod keyboard;
+mod migrations;
+mod roster;
+mod saves;
+mod services;
+mod share;
+mod state;
+mod task_board;
+mod tutorial;
+
+pub mod data_transfer;
+pub mod event_bus;
+pub mod simulation;
+
+use components::game::Game;
+
+use crate::audio::play_zipclick;
+use crate::components::bug;
+use crate::components::game::GameStateOrigin;
+use crate::components::modal::Modal;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    NewProduct,
+    NewGame,
+    MainMenu,
+    LoadMenu,
+    LoadGame(u32),
+    DeleteSlot(u32),
+    BackupSlot(u32),
+    ImportCodeInput(String),
+    ImportFile(File),
+    Import(String),
+    ProductNameInput(String),
+    ToggleOnboarding,
+    SetLocale(Locale),
+    TogglePauseWhenHidden,
+    SetMaxCatchUpTicks(u32),
+    Nothing,
+}
+
+#[derive(Debug)]
+struct App {
+    // `ComponentLink` is like a reference to a component.
+    // It can be used to send messages to the component
+    link: ComponentLink<Self>,
+
+    /// Our local version of state.
+    state: AppState,
+
+    /// The saved games.
+    slots: Vec<SlotInfo>,
+
+    /// The product name input value.
+    product_name: String,
+
+    /// Whether onboarding checkbox is checked.
+    onboarding: bool,
+
+    /// The settings of the game clock.
+    clock: ClockSettings,
+
+    /// The share code input value.
+    import_code: String,
+
+    /// Why the last import failed, if it did.
+    import_error: Option<String>,
+
+    /// The file being read for import.
+    reader: Option<ReaderTask>,
+
+    click_audio: HtmlAudioElement,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AppState {
+    /// The player is in the main menu
+    MainMenu,
+    /// The player is choosing a product name
+    NewProduct,
+    /// The player is choosing a saved game
+    LoadGame,
+    /// The saved game in the given slot could not be loaded
+    BrokenSave { slot: u32, error: String },
+    /// A game is ongoing
+    Game(GameStateOrigin),
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        AppState::MainMenu
+    }
+}
+
+impl Component for App {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        match saves::migrate_legacy_save() {
+            Ok(Some(id)) => gloo_console::log!("Moved old saved game to slot", id),
+            Ok(None) => {}
+            Err(e) => gloo_console::error!("Could not move old saved game:", e),
+        }
+        let slots = load_slots();
+
+        i18n::init_locale();
+
+        let clock = ClockSettings::load().unwrap_or_else(|e| {
+            gloo_console::error!("Could not load clock settings, using defaults:", e);
+            ClockSettings::default()
+        });
+
+        // pre-load sound assets
+        let click_audio = HtmlAudioElement::new_with_src(audio::ZIPCLICK).unwrap_throw();
+        click_audio.set_cross_origin(Some("anonymous"));
+
+        Self {
+            link,
+            //state: AppState::MainMenu,
+            state: AppState::default(),
+            slots,
+            product_name: String::new(),
+            onboarding: true,
+            clock,
+            import_code: String::new(),
+            import_error: None,
+            reader: None,
+            click_audio,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Nothing => false,
+            Msg::NewProduct => {
+                self.state = AppState::NewProduct;
+                true
+            }
+            Msg::MainMenu => {
+                self.state = AppState::MainMenu;
+                true
+            }
+            Msg::LoadMenu => {
+                self.slots = load_slots();
+                self.import_error = None;
+                self.state = AppState::LoadGame;
+                true
+            }
+            Msg::ImportCodeInput(code) => {
+                self.import_code = code;
+                true
+            }
+            Msg::ImportFile(file) => {
+                let callback = self.link.callback(|data: FileData| {
+                    Msg::Import(String::from_utf8_lossy(&data.content).into_owned())
+                });
+                match ReaderService::read_file(file, callback) {
+                    Ok(task) => self.reader = Some(task),
+                    Err(e) => {
+                        gloo_console::error!("Could not read file:", e.to_string());
+                    }
+                }
+                false
+            }
+            Msg::Import(text) => {
+                self.reader = None;
+                let imported = share::import(&text).and_then(|state| {
+                    let name = state.product_name.to_string();
+                    saves::create_slot(&name, &state).map_err(SaveError::from)
+                });
+                match imported {
+                    Ok(_) => {
+                        self.import_code.clear();
+                        self.import_error = None;
+                        self.slots = load_slots();
+                    }
+                    Err(e) => {
+                        gloo_console::error!("Could not import game:", e.to_string());
+                        self.import_error = Some(e.to_string());
+                    }
+                }
+                true
+            }
+            Msg::LoadGame(slot) => {
+                match WorldState::load_from_storage(slot) {
+                    Ok(Some(state)) => {
+                        self.state = AppState::Game(GameStateOrigin::Continue {
+                            slot,
+                            state: Rc::new(state),
+                        });
+                    }
+                    Ok(None) => {
+                        // the slot is gone, refresh the list
+                        self.slots = load_slots();
+                    }
+                    Err(e) => {
+                        gloo_console::error!("Could not load game:", e.to_string());
+                        self.state = AppState::BrokenSave {
+                            slot,
+                            error: e.to_string(),
+                        };
+                    }
+                }
+                true
+            }
+            Msg::BackupSlot(slot) => {
+                saves::download_slot(slot).unwrap_or_else(|e| {
+                    gloo_console::error!("Could not back up saved game:", e);
+                });
+                false
+            }
+            Msg::DeleteSlot(slot) => {
+                saves::delete_slot(slot).unwrap_or_else(|e| {
+                    gloo_console::error!("Could not delete saved game:", e);
+                });
+                self.slots = load_slots();
+                self.state = if self.slots.is_empty() {
+                    AppState::MainMenu
+                } else {
+                    AppState::LoadGame
+                };
+                true
+            }
+            Msg::ProductNameInput(name) => {
+                self.product_name = name;
+                true
+            }
+            Msg::ToggleOnboarding => {
+                self.onboarding = !self.onboarding;
+                true
+            }
+            Msg::SetLocale(locale) => {
+                i18n::set_locale(locale).unwrap_or_else(|e| {
+                    gloo_console::error!("Could not save language settings:", e);
+                });
+                true
+            }
+            Msg::TogglePauseWhenHidden => {
+                self.clock.pause_when_hidden = !self.clock.pause_when_hidden;
+                self.clock.save().unwrap_or_else(|e| {
+                    gloo_console::error!("Could not save clock settings:", e);
+                });
+                true
+            }
+            Msg::SetMaxCatchUpTicks(ticks) => {
+                self.clock.max_catch_up_ticks = ticks.max(1);
+                self.clock.save().unwrap_or_else(|e| {
+                    gloo_console::error!("Could not save clock settings:", e);
+                });
+                true
+            }
+            Msg::NewGame => {
+                self.state = AppState::Game(GameStateOrigin::New {
+                    project_name: self.product_name.clone(),
+                    onboarding: self.onboarding,
+                });
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        // return always false because component has no properties
+        false
+    }
+
+    fn view(&self) -> Html {
+        match &self.state {
+            AppState::MainMenu => {
+                let newgame_handler = self.link.callback(move |_| {
+                    play_zipclick();
+
+                    Msg::NewProduct
+                });
+                let loadgame_handler = self.link.callback(move |_| {
+                    play_zipclick();
+
+                    Msg::LoadMenu
+                });
+                let locale_handler = self.link.callback(|ev: ChangeData| match ev {
+                    ChangeData::Select(select) => Locale::from_code(&select.value())
+                        .map(Msg::SetLocale)
+                        .unwrap_or(Msg::Nothing),
+                    _ => Msg::Nothing,
+                });
+                let pause_when_hidden_handler = self.link.callback(|_| Msg::TogglePauseWhenHidden);
+                let catch_up_handler = self.link.callback(|ev: ChangeData| match ev {
+                    ChangeData::Select(select) => select
+                        .value()
+                        .parse()
+                        .map(Msg::SetMaxCatchUpTicks)
+                        .unwrap_or(Msg::Nothing),
+                    _ => Msg::Nothing,
+                });
+                // a cap set by other means is kept among the choices
+                let current_catch_up = self.clock.max_catch_up_ticks;
+                let mut catch_up_choices = MAX_CATCH_UP_TICKS_CHOICES.to_vec();
+                if !catch_up_choices.contains(&current_catch_up) {
+                    catch_up_choices.push(current_catch_up);
+                    catch_up_choices.sort_unstable();
+                }
+                let catch_up_options = catch_up_choices
+                    .into_iter()
+                    .map(|ticks| {
+                        let label = if ticks <= 1 {
+                            tr("menu.catch_up.none")
+                        } else {
+                            let seconds = ticks * BASE_MILLISECONDS_PER_TICK / 1_000;
+                            tr_count("menu.catch_up.seconds", seconds as i64)
+                        };
+                        html! {
+                            <option value=ticks.to_string() selected={ticks == current_catch_up}>
+                                {label}
+                            </option>
+                        }
+                    })
+                    .collect::<Html>();
+                let current_locale = i18n::current_locale();
+                let locale_options = Locale::ALL
+                    .iter()
+                    .map(|locale| {
+                        html! {
+                            <option value=locale.code() selected={*locale == current_locale}>
+                                {locale.native_name()}
+                            </option>
+                        }
+                    })
+                    .collect::<Html>();
+
+                html! {
+                    <>
+                    <div class="main-menu-back" />
+                    <div class="main-menu">
+                        <h1>{ "10x Sprint Master" }</h1>
+                        <div class="main-menu-prompt">
+                            <button class="main-menu-button" onclick=newgame_handler>{tr("menu.new_game")}</button>
+                            <button class="main-menu-button" onclick=loadgame_handler>{tr("menu.load_game")}</button>
+                        </div>
+                        <div class="main-menu-locale">
+                            <label>
+                                {tr("menu.language")}{" "}
+                                <select onchange=locale_handler>
+                                    {locale_options}
+                                </select>
+                            </label>
+                        </div>
+                        <div class="main-menu-option">
+                            <label>
+                                <input type="checkbox" checked=self.clock.pause_when_hidden onclick=pause_when_hidden_handler />
+                                {" "}{tr("menu.pause_when_hidden")}
+                            </label>
+                        </div>
+                        <div class="main-menu-option">
+                            <label>
+                                {tr("menu.catch_up")}{" "}
+                                <select onchange=catch_up_handler>
+                                    {catch_up_options}
+                                </select>
+                            </label>
+                        </div>
+                        <div class="main-menu-bug">
+                            {bug()}
+                        </div>
+                        <footer><a href="https://github.com/Enet4/10xSprintMaster">{tr("menu.github")}</a></footer>
+                    </div>
+                    </>
+                }
+            }
+            AppState::NewProduct => {
+                let product_name = self.product_name.clone();
+                let input_handler = self
+                    .link
+                    .callback(move |ev: InputData| Msg::ProductNameInput(ev.value));
+                let check_handler = self.link.callback(move |_| Msg::ToggleOnboarding);
+                let click_audio = self.click_audio.clone();
+                let ok_handler = self.link.callback(move |_| {
+                    let _ = click_audio.play().unwrap_throw();
+
+                    Msg::NewGame
+                });
+                let submit_handler = self.link.callback(move |ev: FocusEvent| {
+                    // prevent form submission
+                    ev.prevent_default();
+                    if product_name.is_empty() {
+                        Msg::Nothing
+                    } else {
+                        Msg::NewGame
+                    }
+                });
+
+                html! {
+                    <Modal title=tr("new_game.title")>
+                        <form onsubmit=submit_handler>
+                            <p>{ tr("new_game.prompt") }</p>
+                            <input type="text" class="product-name-input" maxlength=44 placeholder=tr("new_game.placeholder") oninput=input_handler />
+
+                            <p>
+                            <span>
+                                <input type="checkbox" class="onboarding" checked=self.onboarding onclick=check_handler /> {tr("new_game.onboarding")}
+                            </span>
+                            </p>
+                            <p><button onclick=ok_handler disabled={self.product_name.is_empty()}>{tr("common.ok")}</button></p>
+                        </form>
+                    </Modal>
+                }
+            }
+            AppState::LoadGame => {
+                let slots = self
+                    .slots
+                    .iter()
+                    .map(|slot| self.view_slot(slot))
+                    .collect::<Html>();
+                let back_handler = self.link.callback(move |_| {
+                    play_zipclick();
+
+                    Msg::MainMenu
+                });
+
+                html! {
+                    <Modal title=tr("load.title")>
+                        {
+                            if self.slots.is_empty() {
+                                html! { <p>{tr("load.empty")}</p> }
+                            } else {
+                                html! {
+                                    <ul class="save-slots">
+                                        {slots}
+                                    </ul>
+                                }
+                            }
+                        }
+                        {self.view_import()}
+                        <p><button onclick=back_handler>{tr("common.back")}</button></p>
+                    </Modal>
+                }
+            }
+            AppState::BrokenSave { slot, error } => {
+                let slot = *slot;
+                let backup_handler = self.link.callback(move |_| {
+                    play_zipclick();
+
+                    Msg::BackupSlot(slot)
+                });
+                let discard_handler = self.link.callback(move |_| {
+                    if confirm(&tr("broken_save.confirm_discard")) {
+                        Msg::DeleteSlot(slot)
+                    } else {
+                        Msg::Nothing
+                    }
+                });
+                let back_handler = self.link.callback(move |_| {
+                    play_zipclick();
+
+                    Msg::LoadMenu
+                });
+
+                html! {
+                    <Modal title=tr("broken_save.title")>
+                        <p>{tr("broken_save.explanation")}</p>
+                        <pre class="broken-save-error">{error}</pre>
+                        <p>{tr("broken_save.advice")}</p>
+                        <p>
+                            <button onclick=backup_handler>{tr("broken_save.backup")}</button>
+                            <button onclick=discard_handler>{tr("broken_save.discard")}</button>
+                            <button onclick=back_handler>{tr("common.back")}</button>
+                        </p>
+                    </Modal>
+                }
+            }
+            AppState::Game(origin) => html! {
+                <Game state_from={origin.clone()} />
+            },
+        }
+    }
+}
+
+impl App {
+    fn view_import(&self) -> Html {
+        let file_handler = self.link.callback(|ev: ChangeData| match ev {
+            ChangeData::Files(files) => files.get(0).map(Msg::ImportFile).unwrap_or(Msg::Nothing),
+            _ => Msg::Nothing,
+        });
+        let code_handler = self
+            .link
+            .callback(|ev: InputData| Msg::ImportCodeInput(ev.value));
+        let code = self.import_code.clone();
+        let import_handler = self.link.callback(move |_| {
+            play_zipclick();
+
+            Msg::Import(code.clone())
+        });
+
+        html! {
+            <div class="save-import">
+                <h4>{tr("load.import")}</h4>
+                <p>
+                    <label>
+                        {tr("load.import_file")}{" "}
+                        <input type="file" accept=".json,application/json" onchange=file_handler />
+                    </label>
+                </p>
+                <p>{tr("load.import_code")}</p>
+                <textarea class="share-code" value=self.import_code.clone() oninput=code_handler />
+                <p>
+                    <button onclick=import_handler disabled=self.import_code.trim().is_empty()>
+                        {tr("load.import_button")}
+                    </button>
+                </p>
+                {
+                    if let Some(error) = &self.import_error {
+                        html! {
+                            <p class="save-import-error">
+                                {tr("load.import_failed")}{" "}{error}
+                            </p>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        }
+    }
+
+    fn view_slot(&self, slot: &SlotInfo) -> Html {
+        let id = slot.id;
+        let load_handler = self.link.callback(move |_| {
+            play_zipclick();
+
+            Msg::LoadGame(id)
+        });
+        let name = slot.name.clone();
+        let delete_handler = self.link.callback(move |_| {
+            if confirm(&i18n::tr_vars("load.confirm_delete", &[("$NAME", &name)])) {
+                Msg::DeleteSlot(id)
+            } else {
+                Msg::Nothing
+            }
+        });
+
+        html! {
+            <li class="save-slot">
+                <div class="save-slot-info">
+                    <strong>{&slot.name}</strong>
+                    {
+                        if slot.name != slot.product_name {
+                            html! { <span class="save-slot-product">{&slot.product_name}</span> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    <span class="save-slot-details">
+                        {tr_count("status.month", slot.month as i64)}{" · "}
+                        {tr_count("load.score", slot.score as i64)}{" · "}
+                        {slot.saved_at_string()}
+                    </span>
+                </div>
+                <button onclick=load_handler>{tr("load.load")}</button>
+                <button onclick=delete_handler>{tr("load.delete")}</button>
+            </li>
+        }
+    }
+}
+
+/// Ask the player to confirm an action.
+fn confirm(message: &str) -> bool {
+    web_sys::window()
+        .and_then(|w| w.confirm_with_message(message).ok())
+        .unwrap_or(false)
+}
+
+/// Gather the saved games,
+/// showing none if they cannot be read.
+fn load_slots() -> Vec<SlotInfo> {
+    saves::list_slots().unwrap_or_else(|e| {
+        gloo_console::error!("Could not read saved games:", e);
+        vec![]
+    })
+}
+
+/// Start the game in the page.
+pub fn start_app() {
+    yew::start_app::<App>();
+}
@@ -0,0 +1,389 @@
+//! Module for the format of saved games.
+//!
+//! Games are saved as a JSON object with the schema version
+//! and the world state (`{"version": 4, "state": {...}}`).
+//! When loading a save from an older version,
+//! its state goes through the chain of migrations
+//! until it matches the current schema.
+//!
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+
+use crate::state::WorldState;
+
+/// The current schema version of saved games.
+pub const SAVE_VERSION: u32 = 4;
+
+/// A migration of the saved world state from one version to the next.
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// All migrations, the one at index `i` migrates from version `i` to `i + 1`.
+static MIGRATIONS: [Migration; SAVE_VERSION as usize] = [
+    from_unversioned,
+    merge_task_lists,
+    split_roster,
+    drop_hidden_tasks,
+];
+
+/// Version 0: the state was saved as is, without a version.
+///
+/// Fields added since then are filled in with their defaults.
+fn from_unversioned(state: Value) -> Result<Value, String> {
+    if state.is_object() {
+        Ok(state)
+    } else {
+        Err("the saved state is not an object".to_string())
+    }
+}
+
+/// Version 1: the tasks were kept in one list per stage.
+///
+/// They are now kept in a single list,
+/// with the stage of each task taken from the list it was in.
+fn merge_task_lists(mut state: Value) -> Result<Value, String> {
+    const LISTS: [(&str, &str); 5] = [
+        ("tasks_backlog", "backlog"),
+        ("tasks_candidate", "candidate"),
+        ("tasks_progress", "progress"),
+        ("tasks_review", "review"),
+        ("tasks_done", "done"),
+    ];
+
+    let fields = state
+        .as_object_mut()
+        .ok_or_else(|| "the saved state is not an object".to_string())?;
+    let mut tasks = vec![];
+    for (list, stage) in LISTS {
+        let list_tasks = match fields.remove(list) {
+            Some(Value::Array(list_tasks)) => list_tasks,
+            Some(_) => return Err(format!("`{}` is not a list", list)),
+            None => continue,
+        };
+        for mut task in list_tasks {
+            let task_fields = task
+                .as_object_mut()
+                .ok_or_else(|| format!("a task in `{}` is not an object", list))?;
+            task_fields.insert("stage".to_string(), Value::from(stage));
+            tasks.push(task);
+        }
+    }
+    fields.insert("tasks".to_string(), Value::Array(tasks));
+    Ok(state)
+}
+
+/// Version 2: the humans were kept in a single list,
+/// with those who quit marked as such.
+///
+/// They are now kept apart from the team as alumni,
+/// along with the ID of the next human to hire.
+fn split_roster(mut state: Value) -> Result<Value, String> {
+    let fields = state
+        .as_object_mut()
+        .ok_or_else(|| "the saved state is not an object".to_string())?;
+    let humans = match fields.remove("humans") {
+        Some(Value::Array(humans)) => humans,
+        Some(_) => return Err("`humans` is not a list".to_string()),
+        None => vec![],
+    };
+
+    let mut team = vec![];
+    let mut alumni = vec![];
+    let mut next_id = 0;
+    for mut human in humans {
+        let human_fields = human
+            .as_object_mut()
+            .ok_or_else(|| "a human is not an object".to_string())?;
+        let id = human_fields
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| "a human has no ID".to_string())?;
+        next_id = next_id.max(id + 1);
+        match human_fields.remove("quit") {
+            Some(Value::Bool(true)) => alumni.push(human),
+            _ => team.push(human),
+        }
+    }
+
+    let roster = serde_json::json!({
+        "team": team,
+        "alumni": alumni,
+        "next_id": next_id,
+    });
+    fields.insert("humans".to_string(), roster);
+    Ok(state)
+}
+
+/// Version 3: tasks done in past months stayed on the board,
+/// marked as hidden.
+///
+/// Tasks done are now archived when the month ends,
+/// so the hidden ones are dropped,
+/// as they were already reported in their month.
+fn drop_hidden_tasks(mut state: Value) -> Result<Value, String> {
+    let fields = state
+        .as_object_mut()
+        .ok_or_else(|| "the saved state is not an object".to_string())?;
+    let tasks = match fields.get_mut("tasks") {
+        Some(Value::Array(tasks)) => tasks,
+        Some(_) => return Err("`tasks` is not a list".to_string()),
+        None => return Ok(state),
+    };
+    tasks.retain(|task| task.get("visible") != Some(&Value::Bool(false)));
+    for task in tasks {
+        if let Some(task_fields) = task.as_object_mut() {
+            task_fields.remove("visible");
+        }
+    }
+    Ok(state)
+}
+
+/// An error which prevents a saved game from being loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveError {
+    /// local storage could not be accessed
+    Storage(String),
+    /// the save is not valid JSON
+    Malformed(String),
+    /// the save was made by a newer version of the game
+    TooNew(u32),
+    /// the save could not be migrated to the current version
+    Migration { from: u32, reason: String },
+    /// the migrated save does not describe a valid game
+    Invalid(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Storage(reason) => write!(f, "could not access storage: {}", reason),
+            SaveError::Malformed(reason) => write!(f, "malformed save: {}", reason),
+            SaveError::TooNew(version) => write!(
+                f,
+                "save version {} is newer than this game (version {})",
+                version, SAVE_VERSION
+            ),
+            SaveError::Migration { from, reason } => write!(
+                f,
+                "could not migrate save from version {}: {}",
+                from, reason
+            ),
+            SaveError::Invalid(reason) => write!(f, "invalid save: {}", reason),
+        }
+    }
+}
+
+impl From<JsValue> for SaveError {
+    fn from(e: JsValue) -> Self {
+        SaveError::Storage(e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+    }
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    version: u32,
+    state: &'a WorldState,
+}
+
+/// Encode the world state in the current save format.
+pub fn encode(state: &WorldState) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&Payload {
+        version: SAVE_VERSION,
+        state,
+    })
+}
+
+/// Decode a saved game of any known version.
+pub fn decode(data: &str) -> Result<WorldState, SaveError> {
+    let value: Value =
+        serde_json::from_str(data).map_err(|e| SaveError::Malformed(e.to_string()))?;
+    let (version, mut state) = split_version(value)?;
+
+    if version > SAVE_VERSION {
+        return Err(SaveError::TooNew(version));
+    }
+
+    for (from, migration) in (version..SAVE_VERSION).zip(&MIGRATIONS[version as usize..]) {
+        state = migration(state).map_err(|reason| SaveError::Migration { from, reason })?;
+    }
+
+    serde_json::from_value(state).map_err(|e| SaveError::Invalid(e.to_string()))
+}
+
+/// Separate the schema version from the saved state.
+///
+/// Saves without a version are version 0.
+fn split_version(value: Value) -> Result<(u32, Value), SaveError> {
+    match value {
+        Value::Object(mut fields) if fields.contains_key("version") => {
+            let version = fields
+                .get("version")
+                .and_then(Value::as_u64)
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| SaveError::Malformed("bad version number".to_string()))?;
+            let state = fields
+                .remove("state")
+                .ok_or_else(|| SaveError::Malformed("missing state".to_string()))?;
+            Ok((version, state))
+        }
+        value => Ok((0, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::components::stage::StageId;
+
+    fn task(id: u32, stage: &str) -> Value {
+        json!({
+            "id": id,
+            "created": 0,
+            "description": "task.feature",
+            "kind": "normal",
+            "stage": stage,
+            "score": 4,
+            "difficulty": 10,
+            "progress": 0.0,
+            "specified": false,
+            "bugs": 0,
+            "bugs_found": 0,
+            "visible": true,
+        })
+    }
+
+    /// A task done in a past month, still on the board until version 4.
+    fn hidden_task(id: u32) -> Value {
+        let mut task = task(id, "done");
+        task["visible"] = Value::Bool(false);
+        task
+    }
+
+    fn human(id: u32, name: &str, quit: bool) -> Value {
+        json!({
+            "id": id,
+            "name": name,
+            "color": "#fff",
+            "status": "idle",
+            "experience": 50,
+            "progress": 0.0,
+            "quit": quit,
+        })
+    }
+
+    /// The state as it was saved before saves had a version,
+    /// and still in version 1.
+    fn v0_state() -> Value {
+        json!({
+            "product_name": "Old Product",
+            "month": 2,
+            "time": 2_100,
+            "time_in_month": 100,
+            "next_task_id": 356,
+            "total_score": 12_000,
+            "score_in_month": 2_000,
+            "bugs": 3,
+            "bugs_fixed_in_total": 1,
+            "bugs_fixed_in_month": 0,
+            "complexity": 30,
+            "score_linger_rate": 110,
+            "task_ingest_rate": 5,
+            "tasks_backlog": [task(353, "backlog"), task(351, "backlog")],
+            "tasks_candidate": [],
+            "tasks_progress": [task(354, "progress")],
+            "tasks_review": [],
+            "tasks_done": [hidden_task(350), task(352, "done"), task(355, "done")],
+            "humans": [human(0, "You", false), human(1, "Guy", true), human(2, "Ana", false)],
+        })
+    }
+
+    /// The state as it was saved in version 2.
+    fn v2_state() -> Value {
+        let mut state = v0_state();
+        let fields = state.as_object_mut().unwrap();
+        let mut tasks = vec![];
+        for list in ["tasks_backlog", "tasks_progress", "tasks_done"] {
+            if let Some(Value::Array(list)) = fields.remove(list) {
+                tasks.extend(list);
+            }
+        }
+        for list in ["tasks_candidate", "tasks_review"] {
+            fields.remove(list);
+        }
+        fields.insert("tasks".to_string(), Value::Array(tasks));
+        state
+    }
+
+    /// The state as it was saved in version 3.
+    fn v3_state() -> Value {
+        split_roster(v2_state()).unwrap()
+    }
+
+    fn assert_migrated(state: &WorldState) {
+        assert_eq!(&*state.product_name, "Old Product");
+        assert_eq!(state.month, 2);
+
+        let ids = |stage| {
+            state
+                .tasks
+                .in_stage(stage)
+                .map(|t| t.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(StageId::Backlog), [353, 351]);
+        assert_eq!(ids(StageId::Progress), [354]);
+        assert_eq!(ids(StageId::Done), [352, 355]);
+
+        let team: Vec<_> = state.humans.team().map(|h| h.id).collect();
+        assert_eq!(team, [0, 2]);
+        let alumni: Vec<_> = state.humans.alumni().map(|h| h.id).collect();
+        assert_eq!(alumni, [1]);
+    }
+
+    #[test]
+    fn unversioned_saves_are_migrated() {
+        let data = v0_state().to_string();
+        assert_migrated(&decode(&data).unwrap());
+    }
+
+    #[test]
+    fn version_1_saves_are_migrated() {
+        let data = json!({ "version": 1, "state": v0_state() }).to_string();
+        assert_migrated(&decode(&data).unwrap());
+    }
+
+    #[test]
+    fn version_2_saves_are_migrated() {
+        let data = json!({ "version": 2, "state": v2_state() }).to_string();
+        assert_migrated(&decode(&data).unwrap());
+    }
+
+    #[test]
+    fn version_3_saves_are_migrated() {
+        let data = json!({ "version": 3, "state": v3_state() }).to_string();
+        assert_migrated(&decode(&data).unwrap());
+    }
+
+    #[test]
+    fn current_saves_are_read_back() {
+        let state = decode(&v0_state().to_string()).unwrap();
+        let data = encode(&state).unwrap();
+        assert!(data.starts_with(&format!("{{\"version\":{},", SAVE_VERSION)));
+        assert_eq!(decode(&data).unwrap(), state);
+    }
+
+    #[test]
+    fn saves_from_newer_versions_are_rejected() {
+        let data = json!({ "version": SAVE_VERSION + 1, "state": {} }).to_string();
+        assert_eq!(decode(&data), Err(SaveError::TooNew(SAVE_VERSION + 1)));
+
+        // not to be read as version 1
+        let data = json!({ "version": (1_u64 << 32) + 1, "state": v0_state() }).to_string();
+        assert!(matches!(decode(&data), Err(SaveError::Malformed(_))));
+    }
+}
@@ -0,0 +1,310 @@
+//! Module for the registry of humans in the game.
+//!
+//! Humans are kept by ID, which never changes nor gets reused.
+//! Those who leave the team are kept aside as alumni,
+//! so that they can still be named in reports
+//! and be hired again later on.
+//! The roster also records which humans were changed through it,
+//! so that the changes of a request are known without comparing rosters.
+//!
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+
+use crate::components::human::{GameHuman, HumanStatus};
+
+/// All humans who ever were in the team.
+///
+/// It is saved as the list of team members,
+/// the list of alumni and the ID of the next human to hire.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(from = "RosterData")]
+pub struct Roster {
+    /// the humans in the team, by ID
+    team: BTreeMap<u32, GameHuman>,
+    /// the humans who left the team, by ID
+    alumni: BTreeMap<u32, GameHuman>,
+    /// the ID of the next human to hire
+    next_id: u32,
+    /// the IDs of the humans who joined, changed or left
+    /// since they were last taken
+    changed: BTreeSet<u32>,
+    /// whether someone joined or left the team
+    /// since this was last taken
+    team_changed: bool,
+}
+
+impl PartialEq for Roster {
+    fn eq(&self, other: &Self) -> bool {
+        self.team == other.team && self.alumni == other.alumni && self.next_id == other.next_id
+    }
+}
+
+impl Roster {
+    /// Get the human of the given ID, whether in the team or not.
+    pub fn get(&self, id: u32) -> Option<&GameHuman> {
+        self.team.get(&id).or_else(|| self.alumni.get(&id))
+    }
+
+    /// Get the team member of the given ID.
+    pub fn member(&self, id: u32) -> Option<&GameHuman> {
+        self.team.get(&id)
+    }
+
+    /// Get the team member of the given ID for changing it.
+    pub fn member_mut(&mut self, id: u32) -> Option<&mut GameHuman> {
+        let human = self.team.get_mut(&id)?;
+        self.changed.insert(id);
+        Some(human)
+    }
+
+    /// Whether the human of the given ID is in the team.
+    pub fn is_member(&self, id: u32) -> bool {
+        self.team.contains_key(&id)
+    }
+
+    /// The number of humans in the team.
+    pub fn team_size(&self) -> usize {
+        self.team.len()
+    }
+
+    /// The humans in the team, in order of arrival.
+    pub fn team(&self) -> impl Iterator<Item = &GameHuman> {
+        self.team.values()
+    }
+
+    /// Change each human in the team, in order of arrival.
+    pub fn team_mut(&mut self) -> impl Iterator<Item = &mut GameHuman> {
+        self.changed.extend(self.team.keys());
+        self.team.values_mut()
+    }
+
+    /// The humans who left the team, in order of arrival.
+    pub fn alumni(&self) -> impl Iterator<Item = &GameHuman> {
+        self.alumni.values()
+    }
+
+    /// Change each human, team members first.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut GameHuman> {
+        self.changed
+            .extend(self.team.keys().chain(self.alumni.keys()));
+        self.team.values_mut().chain(self.alumni.values_mut())
+    }
+
+    /// Add a new human to the team,
+    /// created from the ID given to them.
+    pub fn hire(&mut self, new_human: impl FnOnce(u32) -> GameHuman) -> &GameHuman {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut human = new_human(id);
+        human.id = id;
+        self.changed.insert(id);
+        self.team_changed = true;
+        self.team.entry(id).or_insert(human)
+    }
+
+    /// Take a human out of the team.
+    ///
+    /// Returns the human who left,
+    /// or `None` if there is no team member with this ID.
+    pub fn leave(&mut self, id: u32) -> Option<&GameHuman> {
+        let mut human = self.team.remove(&id)?;
+        human.status = HumanStatus::Idle;
+        human.progress = 0.;
+        self.changed.insert(id);
+        self.team_changed = true;
+        Some(self.alumni.entry(id).or_insert(human))
+    }
+
+    /// Bring a human who left back into the team.
+    ///
+    /// Returns the human who came back,
+    /// or `None` if there is no former team member with this ID.
+    pub fn rehire(&mut self, id: u32) -> Option<&mut GameHuman> {
+        let human = self.alumni.remove(&id)?;
+        self.changed.insert(id);
+        self.team_changed = true;
+        Some(self.team.entry(id).or_insert(human))
+    }
+
+    /// Replace a human with a newer copy of them,
+    /// whether in the team or not.
+    ///
+    /// Returns `false` if there is no human with this ID.
+    pub fn put(&mut self, human: GameHuman) -> bool {
+        let slot = match self.team.get_mut(&human.id) {
+            Some(slot) => slot,
+            None => match self.alumni.get_mut(&human.id) {
+                Some(slot) => slot,
+                None => return false,
+            },
+        };
+        self.changed.insert(human.id);
+        *slot = human;
+        true
+    }
+
+    /// Take the IDs of the humans who joined, changed or left
+    /// since this was last called,
+    /// along with whether anyone joined or left.
+    pub fn take_changed(&mut self) -> (BTreeSet<u32>, bool) {
+        let team_changed = std::mem::take(&mut self.team_changed);
+        (std::mem::take(&mut self.changed), team_changed)
+    }
+}
+
+impl From<Vec<GameHuman>> for Roster {
+    fn from(humans: Vec<GameHuman>) -> Self {
+        let next_id = humans.iter().map(|h| h.id + 1).max().unwrap_or(0);
+        Roster {
+            team: humans.into_iter().map(|h| (h.id, h)).collect(),
+            alumni: BTreeMap::new(),
+            next_id,
+            changed: BTreeSet::new(),
+            team_changed: false,
+        }
+    }
+}
+
+/// The roster as it is saved.
+#[derive(Deserialize)]
+struct RosterData {
+    team: Vec<GameHuman>,
+    #[serde(default)]
+    alumni: Vec<GameHuman>,
+    next_id: u32,
+}
+
+impl From<RosterData> for Roster {
+    fn from(data: RosterData) -> Self {
+        // never hand out an ID which is already taken
+        let next_id = data
+            .team
+            .iter()
+            .chain(&data.alumni)
+            .map(|h| h.id + 1)
+            .fold(data.next_id, u32::max);
+        Roster {
+            team: data.team.into_iter().map(|h| (h.id, h)).collect(),
+            alumni: data.alumni.into_iter().map(|h| (h.id, h)).collect(),
+            next_id,
+            changed: BTreeSet::new(),
+            team_changed: false,
+        }
+    }
+}
+
+impl Serialize for Roster {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let team: Vec<_> = self.team.values().collect();
+        let alumni: Vec<_> = self.alumni.values().collect();
+        let len = if alumni.is_empty() { 2 } else { 3 };
+        let mut s = serializer.serialize_struct("Roster", len)?;
+        s.serialize_field("team", &team)?;
+        if !alumni.is_empty() {
+            s.serialize_field("alumni", &alumni)?;
+        }
+        s.serialize_field("next_id", &self.next_id)?;
+        s.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn human(id: u32, name: &'static str) -> GameHuman {
+        GameHuman::new(id, name, "#fff", 50)
+    }
+
+    fn ids<'a>(humans: impl Iterator<Item = &'a GameHuman>) -> Vec<u32> {
+        humans.map(|h| h.id).collect()
+    }
+
+    fn team_of_two() -> Roster {
+        Roster::from(vec![human(0, "You"), human(1, "Guy")])
+    }
+
+    #[test]
+    fn hired_humans_get_the_next_id() {
+        let mut roster = team_of_two();
+
+        // whatever ID the new human came with
+        let hired = roster.hire(|id| human(id + 10, "Ana"));
+        assert_eq!(hired.id, 2);
+        assert_eq!(roster.hire(|id| human(id, "Ben")).id, 3);
+        assert_eq!(ids(roster.team()), [0, 1, 2, 3]);
+        assert_eq!(roster.team_size(), 4);
+    }
+
+    #[test]
+    fn humans_who_leave_become_alumni() {
+        let mut roster = team_of_two();
+        roster.member_mut(1).unwrap().status = HumanStatus::Coding;
+
+        let left = roster.leave(1).unwrap();
+        assert_eq!(left.status, HumanStatus::Idle);
+        assert!(!roster.is_member(1));
+        assert_eq!(ids(roster.team()), [0]);
+        assert_eq!(ids(roster.alumni()), [1]);
+        // still known by name
+        assert_eq!(roster.get(1).unwrap().name, "Guy");
+        assert!(roster.member(1).is_none());
+        assert!(roster.member_mut(1).is_none());
+
+        assert!(roster.leave(1).is_none());
+        assert!(roster.leave(7).is_none());
+    }
+
+    #[test]
+    fn rehired_humans_keep_their_id() {
+        let mut roster = team_of_two();
+        roster.leave(1);
+
+        assert_eq!(roster.rehire(1).unwrap().id, 1);
+        assert_eq!(ids(roster.team()), [0, 1]);
+        assert_eq!(roster.alumni().count(), 0);
+        assert!(roster.rehire(1).is_none());
+        assert!(roster.rehire(7).is_none());
+
+        // a rehire takes no new ID
+        assert_eq!(roster.hire(|id| human(id, "Ana")).id, 2);
+    }
+
+    #[test]
+    fn ids_are_never_reused_after_loading() {
+        let mut roster: Roster = serde_json::from_value(serde_json::json!({
+            "team": [human(0, "You")],
+            "alumni": [human(4, "Guy")],
+            "next_id": 2,
+        }))
+        .unwrap();
+        assert_eq!(roster.hire(|id| human(id, "Ana")).id, 5);
+
+        // saved and read back the same
+        let saved = serde_json::to_value(&roster).unwrap();
+        assert_eq!(saved["next_id"], 6);
+        assert_eq!(serde_json::from_value::<Roster>(saved).unwrap(), roster);
+    }
+
+    #[test]
+    fn changes_are_recorded_along_with_team_changes() {
+        let mut roster = team_of_two();
+        assert_eq!(roster.take_changed(), (BTreeSet::new(), false));
+
+        roster.member_mut(1).unwrap().progress = 0.5;
+        assert_eq!(roster.take_changed(), (BTreeSet::from([1]), false));
+
+        roster.leave(1);
+        roster.hire(|id| human(id, "Ana"));
+        assert_eq!(roster.take_changed(), (BTreeSet::from([1, 2]), true));
+
+        roster.rehire(1);
+        assert_eq!(roster.take_changed(), (BTreeSet::from([1]), true));
+        assert_eq!(roster.take_changed(), (BTreeSet::new(), false));
+    }
+}
@@ -0,0 +1,263 @@
+//! Module for managing saved games in local storage.
+//!
+//! Each game is saved in its own slot under the key `save.<id>`,
+//! while the key `saves` holds an index of all slots
+//! with enough information to present them without loading each game.
+//!
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use yew::web_sys;
+
+use crate::{
+    migrations::{self, SaveError},
+    state::{try_local_storage, WorldState},
+};
+
+/// The local storage key of the save slot index.
+const INDEX_KEY: &str = "saves";
+
+/// The local storage key of the single save used in older versions.
+const LEGACY_KEY: &str = "save";
+
+/// A summary of a saved game.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SlotInfo {
+    /// the slot's unique identifier
+    pub id: u32,
+    /// the name of the slot
+    pub name: String,
+    /// the name of the product in development
+    pub product_name: String,
+    /// the current month
+    pub month: u32,
+    /// the total score
+    pub score: u32,
+    /// when the game was last saved,
+    /// in milliseconds since the Unix epoch
+    pub saved_at: f64,
+}
+
+impl SlotInfo {
+    fn of(id: u32, name: String, state: &WorldState) -> Self {
+        SlotInfo {
+            id,
+            name,
+            product_name: state.product_name.to_string(),
+            month: state.month,
+            score: state.total_score / 1000,
+            saved_at: js_sys::Date::now(),
+        }
+    }
+
+    /// The date and time of the last save in the browser's locale.
+    pub fn saved_at_string(&self) -> String {
+        js_sys::Date::new(&JsValue::from_f64(self.saved_at))
+            .to_locale_string("default", &JsValue::UNDEFINED)
+            .into()
+    }
+}
+
+/// Figures on how much the current game takes in local storage.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SaveStats {
+    /// the size of the game once saved, in bytes
+    pub save_size: usize,
+    /// the size of everything in local storage, in bytes,
+    /// if local storage is available
+    pub storage_used: Option<usize>,
+    /// the number of tasks on the board
+    pub tasks_on_board: usize,
+    /// the number of months of archived tasks
+    pub archived_months: usize,
+    /// the number of archived tasks
+    pub archived_tasks: u32,
+    /// the number of entries in the event log
+    pub log_entries: usize,
+    /// the number of metric samples
+    pub samples: usize,
+}
+
+impl SaveStats {
+    /// Measure the given game and the local storage.
+    pub fn of(state: &WorldState) -> Self {
+        SaveStats {
+            save_size: migrations::encode(state)
+                .map(|data| data.len())
+                .unwrap_or(0),
+            storage_used: storage_used().ok(),
+            tasks_on_board: state.tasks.iter().count(),
+            archived_months: state.archive.len(),
+            archived_tasks: state.archive.iter().map(|r| r.tasks()).sum(),
+            log_entries: state.log.len(),
+            samples: state.samples.len(),
+        }
+    }
+}
+
+/// Add up the sizes of all keys and values in local storage.
+fn storage_used() -> Result<usize, JsValue> {
+    let local_storage = try_local_storage()?;
+    let mut total = 0;
+    for i in 0..local_storage.length()? {
+        if let Some(key) = local_storage.key(i)? {
+            let value = local_storage.get_item(&key)?.unwrap_or_default();
+            total += key.len() + value.len();
+        }
+    }
+    Ok(total)
+}
+
+fn slot_key(id: u32) -> String {
+    format!("save.{}", id)
+}
+
+fn to_js_error(e: serde_json::Error) -> JsValue {
+    JsValue::from(e.to_string())
+}
+
+fn write_index(slots: &[SlotInfo]) -> Result<(), JsValue> {
+    let local_storage = try_local_storage()?;
+    let data = serde_json::to_string(slots).map_err(to_js_error)?;
+    local_storage.set_item(INDEX_KEY, &data)
+}
+
+/// List all save slots, most recently saved first.
+pub fn list_slots() -> Result<Vec<SlotInfo>, JsValue> {
+    let mut slots = read_index()?;
+    slots.sort_by(|a, b| {
+        b.saved_at
+            .partial_cmp(&a.saved_at)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(slots)
+}
+
+fn read_index() -> Result<Vec<SlotInfo>, JsValue> {
+    let local_storage = try_local_storage()?;
+    match local_storage.get_item(INDEX_KEY)? {
+        Some(data) => serde_json::from_str(&data).map_err(to_js_error),
+        None => Ok(vec![]),
+    }
+}
+
+/// Move a game saved by an older version of the game
+/// to a slot of its own, returning the new slot's ID.
+///
+/// Meant to run once at startup.
+/// The old save is only removed
+/// once the new slot is known to hold the same data.
+pub fn migrate_legacy_save() -> Result<Option<u32>, JsValue> {
+    let local_storage = try_local_storage()?;
+
+    let data = match local_storage.get_item(LEGACY_KEY)? {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    let mut slots = read_index()?;
+    let id = next_slot_id(&slots);
+    local_storage.set_item(&slot_key(id), &data)?;
+    if local_storage.get_item(&slot_key(id))?.as_deref() != Some(data.as_str()) {
+        return Err(JsValue::from_str("old saved game could not be copied"));
+    }
+
+    let slot = match migrations::decode(&data) {
+        Ok(state) => SlotInfo::of(id, state.product_name.to_string(), &state),
+        // keep it anyway, the problem is reported when loading
+        Err(_) => SlotInfo {
+            id,
+            name: "?".to_string(),
+            product_name: "?".to_string(),
+            month: 0,
+            score: 0,
+            saved_at: js_sys::Date::now(),
+        },
+    };
+    slots.push(slot);
+    write_index(&slots)?;
+
+    local_storage.remove_item(LEGACY_KEY)?;
+    Ok(Some(id))
+}
+
+fn next_slot_id(slots: &[SlotInfo]) -> u32 {
+    slots.iter().map(|s| s.id + 1).max().unwrap_or(0)
+}
+
+/// Reserve a new save slot with the given name.
+pub fn create_slot(name: &str, state: &WorldState) -> Result<u32, JsValue> {
+    let mut slots = list_slots()?;
+    let id = next_slot_id(&slots);
+    slots.push(SlotInfo::of(id, name.to_string(), state));
+    write_index(&slots)?;
+    save_slot(id, state)?;
+    Ok(id)
+}
+
+/// Save the whole state of the game onto the given slot.
+pub fn save_slot(id: u32, state: &WorldState) -> Result<(), JsValue> {
+    let local_storage = try_local_storage()?;
+
+    let data = migrations::encode(state).map_err(to_js_error)?;
+    local_storage.set_item(&slot_key(id), &data)?;
+
+    let mut slots = list_slots()?;
+    match slots.iter_mut().find(|s| s.id == id) {
+        Some(slot) => {
+            let name = std::mem::take(&mut slot.name);
+            *slot = SlotInfo::of(id, name, state);
+        }
+        None => slots.push(SlotInfo::of(id, state.product_name.to_string(), state)),
+    }
+    write_index(&slots)
+}
+
+/// Load the whole state of the game from the given slot,
+/// migrating it from older versions if necessary.
+pub fn load_slot(id: u32) -> Result<Option<WorldState>, SaveError> {
+    match raw_slot(id)? {
+        Some(data) => migrations::decode(&data).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Obtain the saved game in the given slot exactly as stored.
+pub fn raw_slot(id: u32) -> Result<Option<String>, JsValue> {
+    let local_storage = try_local_storage()?;
+    local_storage.get_item(&slot_key(id))
+}
+
+/// Offer the saved game in the given slot as a file download.
+pub fn download_slot(id: u32) -> Result<(), JsValue> {
+    let data = raw_slot(id)?.unwrap_or_default();
+    download_file(&format!("10xsprintmaster-save-{}.json", id), &data)
+}
+
+/// Offer the given text as a file download.
+pub fn download_file(file_name: &str, content: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(content));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("application/json");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let anchor: web_sys::HtmlAnchorElement =
+        yew::utils::document().create_element("a")?.dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url)
+}
+
+/// Delete the given save slot.
+pub fn delete_slot(id: u32) -> Result<(), JsValue> {
+    let local_storage = try_local_storage()?;
+
+    local_storage.remove_item(&slot_key(id))?;
+
+    let mut slots = list_slots()?;
+    slots.retain(|s| s.id != id);
+    write_index(&slots)
+}
@@ -1,20 +1,33 @@
 use gloo_timers::callback::Interval;
-use rand::{Rng, SeedableRng};
+use rand::{distributions::WeightedIndex, Rng, SeedableRng};
 use rand_distr::{self, Distribution};
 use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display};
-use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
 
 use crate::{
+    catalog::{template_vars, EventCatalog, EventEffect, StateChanges},
     components::{
         human::GameHuman,
+        messages::Message,
         task::{GameTask, GameTaskBuilder, TaskKind},
     },
-    state::WorldState,
+    dependencies::Dependency,
+    state::{try_local_storage, Timestamp, WorldState},
 };
 
 pub const BASE_MILLISECONDS_PER_TICK: u32 = 200;
 
+/// The default maximum number of ticks run at once
+/// to catch up with the time which passed,
+/// worth a few seconds of play at normal speed.
+pub const DEFAULT_MAX_CATCH_UP_TICKS: u32 = 25;
+
+/// The maximum numbers of ticks to catch up with offered in the menu,
+/// from none at all to a few minutes of play at normal speed.
+pub const MAX_CATCH_UP_TICKS_CHOICES: [u32; 5] = [1, DEFAULT_MAX_CATCH_UP_TICKS, 150, 300, 1_500];
+
 #[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
 pub enum GameSpeed {
     /// 1x game speed
@@ -36,16 +49,67 @@ impl GameSpeed {
     }
 }
 
-/// The time watch service, emits ticks at a fixed interval when started.
+/// The settings of the game clock, kept in local storage.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockSettings {
+    /// the maximum number of ticks run at once
+    /// after the browser held back the clock,
+    /// the rest of the time is skipped
+    pub max_catch_up_ticks: u32,
+    /// whether to pause the game when its tab is hidden
+    pub pause_when_hidden: bool,
+}
+
+impl Default for ClockSettings {
+    fn default() -> Self {
+        ClockSettings {
+            max_catch_up_ticks: DEFAULT_MAX_CATCH_UP_TICKS,
+            pause_when_hidden: false,
+        }
+    }
+}
+
+impl ClockSettings {
+    /// Load the clock settings from local storage,
+    /// or the defaults if none were saved yet.
+    pub fn load() -> Result<Self, JsValue> {
+        let local_storage = try_local_storage()?;
+        match local_storage.get_item("clock")? {
+            Some(data) => {
+                serde_json::from_str(&data).map_err(|e| JsValue::from_str(&e.to_string()))
+            }
+            None => Ok(ClockSettings::default()),
+        }
+    }
+
+    /// Save the clock settings to local storage.
+    pub fn save(&self) -> Result<(), JsValue> {
+        let data = serde_json::to_string(self).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let local_storage = try_local_storage()?;
+        local_storage.set_item("clock", &data)?;
+        Ok(())
+    }
+}
+
+/// The time watch service, emits ticks as game time passes when started.
+///
+/// Browsers delay timers in background tabs,
+/// so the number of ticks is taken from the real time elapsed
+/// rather than from the number of times the timer fired.
 pub struct GameWatch {
     interval: Option<Interval>,
     speed: GameSpeed,
+    /// the maximum number of ticks emitted at once
+    max_catch_up_ticks: u32,
 }
 
 impl Debug for GameWatch {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("GameWatch")
             .field("interval", &self.interval)
+            .field("speed", &self.speed)
+            .field("max_catch_up_ticks", &self.max_catch_up_ticks)
             .finish()
     }
 }
@@ -55,9 +119,17 @@ impl GameWatch {
         GameWatch {
             interval: None,
             speed: GameSpeed::Normal,
+            max_catch_up_ticks: DEFAULT_MAX_CATCH_UP_TICKS,
         }
     }
 
+    /// Set the maximum number of ticks to emit at once
+    /// when catching up with the time which passed,
+    /// taking effect the next time the watch starts.
+    pub fn set_max_catch_up_ticks(&mut self, ticks: u32) {
+        self.max_catch_up_ticks = ticks.max(1);
+    }
+
     pub fn start_with<F>(&mut self, tick_fn: F)
     where
         F: 'static + FnMut() + Clone,
@@ -67,9 +139,7 @@ impl GameWatch {
             return;
         }
 
-        let ms = self.speed.milliseconds_per_tick();
-        let interval = Interval::new(ms, tick_fn);
-        self.interval = Some(interval);
+        self.interval = Some(self.run(self.speed, tick_fn));
     }
 
     /// Set the new game speed,
@@ -86,9 +156,7 @@ impl GameWatch {
         self.pause();
 
         // replace existing interval
-        let interval = Interval::new(speed.milliseconds_per_tick(), tick_fn);
-
-        self.interval = Some(interval);
+        self.interval = Some(self.run(speed, tick_fn));
         self.speed = speed;
     }
 
@@ -103,6 +171,34 @@ impl GameWatch {
     pub fn current_speed(&self) -> Option<GameSpeed> {
         self.interval.as_ref().map(|_| self.speed)
     }
+
+    /// Start a timer which emits as many ticks
+    /// as are due since the last time it fired.
+    fn run<F>(&self, speed: GameSpeed, mut tick_fn: F) -> Interval
+    where
+        F: 'static + FnMut() + Clone,
+    {
+        let ms = speed.milliseconds_per_tick();
+        let max_ticks = self.max_catch_up_ticks;
+        // the time at which the next tick is due
+        let mut due = js_sys::Date::now() + ms as f64;
+        Interval::new(ms, move || {
+            let now = js_sys::Date::now();
+            if now < due {
+                return;
+            }
+            let ticks = ((now - due) / ms as f64) as u32 + 1;
+            if ticks > max_ticks {
+                // skip the time which could not be caught up with
+                due = now + ms as f64;
+            } else {
+                due += ticks as f64 * ms as f64;
+            }
+            for _ in 0..ticks.min(max_ticks) {
+                tick_fn();
+            }
+        })
+    }
 }
 
 impl Display for GameSpeed {
@@ -120,30 +216,56 @@ impl Display for GameSpeed {
 pub struct EventReactor {
     /// the random number generator
     rng: Pcg32,
+    /// the catalog of major events
+    catalog: EventCatalog,
 }
 
 /// Some major event that can happen over time.
 #[derive(Debug)]
 pub enum GameEvent {
-    /// extra technical debt
-    ExtraTechnicalDebt { message: u32, extra_complexity: u32 },
+    /// extra technical debt due to an outdated dependency
+    ExtraTechnicalDebt {
+        message: Message,
+        extra_complexity: u32,
+        /// a chore to migrate the dependency
+        migration: GameTaskBuilder,
+    },
     /// extraordinary features were requested
     MajorFeatureRequested {
-        message: u32,
+        message: Message,
         tasks: Vec<GameTaskBuilder>,
     },
     /// a human quit
-    HumanQuit(u32),
+    HumanQuit { message: Message, human_id: u32 },
     /// a bug was reported by clients
-    BugReported(GameTaskBuilder),
+    BugReported {
+        message: Message,
+        task: GameTaskBuilder,
+    },
     /// Just show a random report
-    RandomReport(u32),
+    RandomReport(Message),
+}
+
+/// A major event rolled from the event catalog,
+/// along with the changes it makes to the world state.
+#[derive(Debug)]
+pub struct MajorEvent {
+    pub event: GameEvent,
+    pub changes: StateChanges,
 }
 
 impl EventReactor {
     pub fn new() -> Self {
+        Self::with_catalog(EventCatalog::bundled())
+    }
+
+    /// Create an event reactor which rolls major events
+    /// from the given catalog,
+    /// as validated by [`EventCatalog::from_json`].
+    pub fn with_catalog(catalog: EventCatalog) -> Self {
         EventReactor {
             rng: Pcg32::from_entropy(),
+            catalog,
         }
     }
 
@@ -207,60 +329,134 @@ impl EventReactor {
         (damage as u32).clamp(0, total_score / 5)
     }
 
-    /// Roll for whether to have a major event
-    pub fn major_event(&mut self, state: &WorldState) -> Option<GameEvent> {
-        match self.rng.gen_range(1..=1_000) {
-            0..=35 => {
-                if state.bugs == 0 {
-                    return None;
-                }
+    /// Roll for whether to have a major event,
+    /// as described by the event catalog.
+    pub fn major_event(&mut self, state: &WorldState) -> Option<MajorEvent> {
+        let EventReactor { rng, catalog } = self;
+
+        let roll = rng.gen_range(0..catalog.roll);
+        let spec = catalog.event_at(roll)?;
 
+        if !spec.conditions.are_met(state) {
+            return None;
+        }
+
+        let mut vars = template_vars(state);
+        let template = &spec.messages[rng.gen_range(0..spec.messages.len())];
+
+        let event = match &spec.effect {
+            EventEffect::Report => GameEvent::RandomReport(template.to_message(&vars)),
+            EventEffect::BugReported { score, difficulty } => {
                 let task = GameTaskBuilder::new(
                     "",
                     TaskKind::Bug,
-                    self.rng.gen_range(0..2),
-                    self.rng.gen_range(2..12),
+                    rng.gen_range(score.0..=score.1),
+                    rng.gen_range(difficulty.0..=difficulty.1),
                 );
 
-                Some(GameEvent::BugReported(task))
+                GameEvent::BugReported {
+                    message: template.to_message(&vars),
+                    task,
+                }
             }
-            36..=89 => {
-                // someone quits due to project complexity
-                let human_count = state.humans.iter().filter(|h| !h.quit).count();
-                let complexity = state.complexity;
-                let pressure = complexity as u32 + human_count as u32 * 5;
-                if human_count > 1 && pressure >= 116 {
-                    // pick a random human to quit
-                    let index = self.rng.gen_range(1..=human_count - 1);
-                    let human = state.humans.iter().filter(|h| !h.quit).nth(index).unwrap();
-
-                    Some(GameEvent::HumanQuit(human.id))
-                } else {
-                    None
+            EventEffect::HumanQuit => {
+                // pick a random human to quit (never You)
+                let candidates = state.humans.team().filter(|h| h.id != 0).count();
+                if candidates == 0 {
+                    return None;
+                }
+                let index = rng.gen_range(0..candidates);
+                let human = state.humans.team().filter(|h| h.id != 0).nth(index)?;
+
+                vars.insert("$HUMAN_NAME", human.name.to_string());
+
+                GameEvent::HumanQuit {
+                    message: template.to_message(&vars),
+                    human_id: human.id,
                 }
             }
-            950..=1_000 => {
-                let n_new_tasks = self.rng.gen_range(1..=3);
+            EventEffect::FeatureRequested {
+                description,
+                tasks,
+                score,
+                difficulty,
+            } => {
+                let n_new_tasks = rng.gen_range(tasks.0..=tasks.1);
 
                 let tasks = (0..n_new_tasks)
                     .map(|_| {
                         GameTaskBuilder::new_with_deadline(
-                            "Extraordinary task",
+                            description.clone(),
                             TaskKind::Normal,
-                            // generally higher score than usual
-                            self.rng.gen_range(4..=16),
-                            // generally harder too
-                            self.rng.gen_range(3..15),
+                            rng.gen_range(score.0..=score.1),
+                            rng.gen_range(difficulty.0..=difficulty.1),
                         )
                     })
                     .collect();
 
-                Some(GameEvent::MajorFeatureRequested { message: 0, tasks })
+                GameEvent::MajorFeatureRequested {
+                    message: template.to_message(&vars),
+                    tasks,
+                }
+            }
+            EventEffect::OutdatedDependency {
+                min_age,
+                complexity_per_version,
+                complexity_per_month,
+                difficulty_per_version,
+            } => {
+                let time = state.time;
+                let outdated: Vec<&Dependency> = state
+                    .dependencies
+                    .iter()
+                    .filter(|d| d.versions_behind() > 0 && d.age(time) >= *min_age)
+                    .collect();
+
+                // older and more outdated dependencies
+                // are more likely to cause trouble
+                let weights = outdated
+                    .iter()
+                    .map(|d| d.versions_behind() * (1 + d.age_in_months(time)));
+                let dist = WeightedIndex::new(weights).ok()?;
+                let dependency = outdated[dist.sample(rng)];
+
+                let versions_behind = dependency.versions_behind();
+                let extra_complexity = versions_behind * complexity_per_version
+                    + dependency.age_in_months(time) * complexity_per_month;
+
+                vars.insert("$DEPENDENCY", dependency.name.to_string());
+                vars.insert("$VERSION", dependency.version.to_string());
+                vars.insert("$LATEST", dependency.latest.to_string());
+
+                // described from the dependency when shown
+                let migration = GameTaskBuilder::new(
+                    "task.migrate",
+                    TaskKind::Chore,
+                    0,
+                    2 + versions_behind * difficulty_per_version,
+                )
+                .migrating(dependency.id);
+
+                GameEvent::ExtraTechnicalDebt {
+                    message: template.to_message(&vars),
+                    extra_complexity,
+                    migration,
+                }
             }
-            750..=899 => Some(GameEvent::RandomReport(self.rng.gen_range(0..=8))),
-            _ => {
-                // do nothing
-                None
+        };
+
+        Some(MajorEvent {
+            event,
+            changes: spec.changes,
+        })
+    }
+
+    /// Roll for new major versions of the product's dependencies
+    /// being released upstream.
+    pub fn release_dependencies(&mut self, dependencies: &mut [Dependency], time: Timestamp) {
+        for dependency in dependencies {
+            if self.rng.gen_ratio(1, 5) {
+                dependency.release(time);
             }
         }
     }
@@ -369,6 +565,34 @@ impl EventReactor {
 
         GameHuman::new(id, HUMAN_NAMES[n], HUMAN_COLORS[n], experience)
     }
+
+    /// Generate a contractor who stays in the team until the end of the given month
+    pub fn new_contractor(&mut self, id: u32, month: u32) -> GameHuman {
+        let dist = rand_distr::Normal::new(40_f32, 10.).unwrap_throw();
+        let experience = dist.sample(&mut self.rng).clamp(25., 60.) as u32;
+
+        let n = self.rng.gen_range(0..CONTRACTOR_NAMES.len());
+
+        let mut human = GameHuman::new(id, CONTRACTOR_NAMES[n], CONTRACTOR_COLORS[n], experience);
+        human.contract_until = Some(month);
+        human
+    }
+
+    /// Roll for a former member of the team to come back,
+    /// instead of hiring someone new
+    pub fn returning_human<'a>(
+        &mut self,
+        alumni: impl Iterator<Item = &'a GameHuman>,
+    ) -> Option<u32> {
+        if !self.rng.gen_ratio(1, 3) {
+            return None;
+        }
+        let alumni: Vec<u32> = alumni.map(|h| h.id).collect();
+        if alumni.is_empty() {
+            return None;
+        }
+        Some(alumni[self.rng.gen_range(0..alumni.len())])
+    }
 }
 
 static HUMAN_NAMES: [&'static str; 16] = [
@@ -380,3 +604,7 @@ static HUMAN_COLORS: [&'static str; 16] = [
     "#00d", "#dd0", "#c6c", "#0cc", "#0dd", "#ccc", "#d00", "#6c0", "#d0d", "#c0c", "#ddc", "#cdd",
     "#f7f", "#3f7", "#f73", "#7f3",
 ];
+
+static CONTRACTOR_NAMES: [&str; 4] = ["Max", "Lee", "Ada", "Kai"];
+
+static CONTRACTOR_COLORS: [&str; 4] = ["#999", "#a97", "#79a", "#9a7"];
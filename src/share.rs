@@ -0,0 +1,105 @@
+//! Module for moving saved games out of and into the browser.
+//!
+//! A game can be exported as a JSON file (the same format as in local storage)
+//! or as a share code:
+//! the same JSON compressed and encoded as URL-safe text,
+//! which is short enough to paste between machines or into a bug report.
+//!
+
+use miniz_oxide::inflate::TINFLStatus;
+use wasm_bindgen::JsValue;
+
+use crate::{
+    migrations::{self, SaveError},
+    saves,
+    state::WorldState,
+};
+
+/// The prefix of all share codes.
+const CODE_PREFIX: &str = "10x:";
+
+/// The compression level of share codes (0 to 10).
+const CODE_COMPRESSION: u8 = 9;
+
+/// The largest save which a share code may unpack to, in bytes.
+/// This is well above what a long game takes,
+/// and about the size of the local storage of a browser.
+const MAX_SAVE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Offer the world state as a JSON file download.
+pub fn download_state(state: &WorldState) -> Result<(), JsValue> {
+    let data = migrations::encode(state).map_err(|e| JsValue::from(e.to_string()))?;
+    let file_name = format!("{}.10x.json", file_name_safe(&state.product_name));
+    saves::download_file(&file_name, &data)
+}
+
+fn file_name_safe(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() {
+        "10xsprintmaster".to_string()
+    } else {
+        name
+    }
+}
+
+/// Produce the share code of the world state.
+pub fn to_code(state: &WorldState) -> Result<String, serde_json::Error> {
+    let data = migrations::encode(state)?;
+    let compressed = miniz_oxide::deflate::compress_to_vec(data.as_bytes(), CODE_COMPRESSION);
+    Ok(format!(
+        "{}{}",
+        CODE_PREFIX,
+        base64::encode_config(compressed, base64::URL_SAFE_NO_PAD)
+    ))
+}
+
+/// Import a game from either a share code or the content of a save file.
+pub fn import(text: &str) -> Result<WorldState, SaveError> {
+    let text = text.trim();
+    match text.strip_prefix(CODE_PREFIX) {
+        Some(code) => from_code(code),
+        None => migrations::decode(text),
+    }
+}
+
+fn from_code(code: &str) -> Result<WorldState, SaveError> {
+    // tolerate line breaks and spaces added when pasting
+    let code: String = code.split_whitespace().collect();
+    let compressed = base64::decode_config(&code, base64::URL_SAFE_NO_PAD)
+        .map_err(|e| SaveError::Malformed(e.to_string()))?;
+    let data = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_SAVE_SIZE)
+        .map_err(|e| match e {
+            TINFLStatus::HasMoreOutput => {
+                SaveError::Malformed(format!("save is larger than {} bytes", MAX_SAVE_SIZE))
+            }
+            e => SaveError::Malformed(format!("{:?}", e)),
+        })?;
+    let data = String::from_utf8(data).map_err(|e| SaveError::Malformed(e.to_string()))?;
+    migrations::decode(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn games_come_back_from_their_share_code() {
+        let state = WorldState::dummy();
+        let code = to_code(&state).unwrap();
+        assert!(code.starts_with(CODE_PREFIX));
+
+        assert_eq!(import(&code).unwrap(), state);
+    }
+
+    #[test]
+    fn codes_unpacking_to_huge_saves_are_rejected() {
+        let data = vec![b' '; MAX_SAVE_SIZE + 1];
+        let compressed = miniz_oxide::deflate::compress_to_vec(&data, CODE_COMPRESSION);
+        let code = base64::encode_config(compressed, base64::URL_SAFE_NO_PAD);
+
+        assert!(matches!(from_code(&code), Err(SaveError::Malformed(_))));
+    }
+}
@@ -0,0 +1,177 @@
+//! Module for running the simulation of the game in a web worker.
+//!
+//! The worker owns the world state and the event reactor,
+//! applies every request of the game component to them,
+//! and answers with the outcome and the parts of the state which changed:
+//! the tasks and humans changed by ID, and the counters.
+//! The game component keeps a copy of the state,
+//! which it patches and only reads to render the game and save it.
+//!
+
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    ser::Error as _,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use yew::worker::{Agent, AgentLink, HandlerId, Public};
+
+use crate::{
+    changes::{ChangeSet, StatePatch},
+    event_bus::EventBusRequest,
+    i18n::{self, Locale},
+    services::EventReactor,
+    state::{EventOutcome, WorldState},
+};
+
+/// A value carried between the page and the worker as JSON.
+///
+/// The binary format of agent messages cannot read back
+/// structures which leave out empty fields, as the world state does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> Serialize for Json<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let text = serde_json::to_string(&self.0).map_err(S::Error::custom)?;
+        serializer.serialize_str(&text)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Json<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        serde_json::from_str(&text)
+            .map(Json)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// A message from the game component to the simulation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SimulationInput {
+    /// Start simulating the given game,
+    /// presenting messages in the given locale
+    Start {
+        state: Box<WorldState>,
+        locale: Locale,
+    },
+    /// Apply a request to the game
+    Request(EventBusRequest),
+    /// Let time pass again after the player read a message
+    Resume,
+}
+
+/// A message from the simulation to the game component,
+/// in answer to each input in the same order.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimulationOutput {
+    /// the parts of the world state which changed
+    pub patch: StatePatch,
+    /// what the input led to
+    pub outcome: EventOutcome,
+    /// what changed in the game, for rendering
+    pub changed: ChangeSet,
+}
+
+/// The agent running the simulation.
+pub struct Simulation {
+    link: AgentLink<Self>,
+
+    /// The game being simulated, once started.
+    state: Option<WorldState>,
+
+    /// Producer of random events.
+    reactor: EventReactor,
+
+    /// Whether time stands still until the game resumes,
+    /// as the player has a message to read.
+    waiting: bool,
+}
+
+impl Agent for Simulation {
+    type Reach = Public<Self>;
+    type Message = ();
+    type Input = Json<SimulationInput>;
+    type Output = Json<SimulationOutput>;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Simulation {
+            link,
+            state: None,
+            reactor: EventReactor::new(),
+            waiting: false,
+        }
+    }
+
+    fn update(&mut self, _msg: Self::Message) {}
+
+    fn handle_input(&mut self, Json(input): Self::Input, who: HandlerId) {
+        let (outcome, changed) = match input {
+            SimulationInput::Start { state, locale } => {
+                i18n::use_locale(locale);
+                self.state = Some(*state);
+                self.waiting = false;
+                (EventOutcome::Nothing, ChangeSet::default())
+            }
+            SimulationInput::Resume => {
+                self.waiting = false;
+                (EventOutcome::Nothing, ChangeSet::default())
+            }
+            // ticks which were already on their way
+            // when a message opened are dropped
+            SimulationInput::Request(EventBusRequest::Tick) if self.waiting => {
+                (EventOutcome::Nothing, ChangeSet::default())
+            }
+            SimulationInput::Request(request) => self.apply(request),
+        };
+
+        if matches!(
+            outcome,
+            EventOutcome::OpenMessage(_) | EventOutcome::EndOfMonth(_)
+        ) {
+            self.waiting = true;
+        }
+
+        let patch = match &self.state {
+            Some(state) => StatePatch::of(state, &changed),
+            None => StatePatch::default(),
+        };
+        self.link.respond(
+            who,
+            Json(SimulationOutput {
+                patch,
+                outcome,
+                changed,
+            }),
+        );
+    }
+
+    fn name_of_resource() -> &'static str {
+        "simulation.js"
+    }
+}
+
+impl Simulation {
+    fn apply(&mut self, request: EventBusRequest) -> (EventOutcome, ChangeSet) {
+        let state = match &mut self.state {
+            Some(state) => state,
+            None => {
+                gloo_console::warn!("Simulation request before the game started");
+                return (EventOutcome::Nothing, ChangeSet::default());
+            }
+        };
+        match state.apply_event(request, &mut self.reactor) {
+            Ok(applied) => applied,
+            Err((e, changes)) => {
+                gloo_console::warn!("Board request failed:", e.to_string());
+                (EventOutcome::Alert(e.message_id().into()), changes)
+            }
+        }
+    }
+}
@@ -1,27 +1,43 @@
 //! Module for handling the state of the game.
 //!
 //!
-use std::{collections::HashSet, rc::Rc};
+use std::{borrow::Cow, collections::HashSet, fmt, rc::Rc};
 
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::{JsValue, UnwrapThrowExt};
 use yew::web_sys;
 
 use crate::{
+    analytics::{self, Sample, TICKS_PER_SAMPLE},
+    archive::{self, ArchivedMonth},
+    catalog::StateChanges,
+    changes::{ChangeSet, Snapshot},
     components::{
-        human::{GameHuman, HumanStatus},
+        human::{GameHuman, HumanStats, HumanStatus},
         messages::Message,
         stage::StageId,
         task::{GameTask, GameTaskBuilder, TaskKind},
     },
     data_transfer::payload::TaskTransfer,
+    dependencies::{initial_dependencies, Dependency},
     event_bus::EventBusRequest,
-    services::{EventReactor, GameEvent},
+    event_log::{self, LogCategory, LogEntry},
+    history::{BoardAction, History},
+    i18n::{tr, tr_vars},
+    migrations::SaveError,
+    roster::Roster,
+    saves,
+    services::{EventReactor, GameEvent, MajorEvent},
+    task_board::TaskBoard,
+    tutorial::{self, TutorialEffect, TutorialEvent},
 };
 
 /// The number of ticks events in a full game month.
 pub const TICKS_PER_MONTH: u32 = 1_000;
 
+/// The number of ticks in a working day.
+pub const TICKS_PER_DAY: u32 = TICKS_PER_MONTH / 20;
+
 /// The number of ticks for for a major tick event
 /// to be triggered.
 pub const TICKS_PER_MAJOR_TICK: u32 = 250;
@@ -35,6 +51,10 @@ pub const TICKS_PER_SCORE_DAMAGE: u32 = 25;
 /// The number of 1000ths of points required to reach the victory condition
 const CPO_SCORE_THRESHOLD: u32 = 2_000_000;
 
+/// The share of development progress kept
+/// when a task is handed over to another developer.
+const HANDOVER_PROGRESS_KEPT: f64 = 0.75;
+
 /// A type for a partiular moment in game time.
 /// This is the number of ticks since the creation of a new game.
 pub type Timestamp = u32;
@@ -84,23 +104,21 @@ pub struct WorldState {
     /// the rate at which You can devise new tasks
     pub task_ingest_rate: u32,
 
-    /// the list of tasks in backlog
-    pub tasks_backlog: Vec<GameTask>,
-
-    /// the list of tasks in sprint candidate
-    pub tasks_candidate: Vec<GameTask>,
+    /// the tasks on the board, in all stages
+    pub tasks: TaskBoard,
 
-    /// the list of tasks in progress
-    pub tasks_progress: Vec<GameTask>,
-
-    /// the list of tasks under review
-    pub tasks_review: Vec<GameTask>,
+    /// summaries of the tasks merged in past months,
+    /// by month
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub archive: Vec<ArchivedMonth>,
 
-    /// the list of tasks done
-    pub tasks_done: Vec<GameTask>,
+    /// All human resources, in the team or not
+    pub humans: Roster,
 
-    /// A list of all human resources
-    pub humans: Vec<GameHuman>,
+    /// The software dependencies of the product
+    #[serde(default = "initial_dependencies")]
+    pub dependencies: Vec<Dependency>,
 
     /// The current tutorial phase, if currently in the tutorial.
     /// Starts at 0, the first message is given at 1.
@@ -108,18 +126,52 @@ pub struct WorldState {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tutorial: Option<u32>,
 
+    /// The time at which the current tutorial phase was entered
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub tutorial_since: Timestamp,
+
     /// Whether the CEO has already contacted you
     #[serde(default)]
     #[serde(skip_serializing_if = "is_false")]
     pub ceo_message_delivered: bool,
+
+    /// What happened in the game so far, oldest first
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub log: Vec<LogEntry>,
+
+    /// Samples of the game's metrics over time, oldest first
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub samples: Vec<Sample>,
+
+    /// The board actions of the player which can be undone or redone
+    #[serde(skip)]
+    pub history: History,
 }
 
 fn is_false(x: &bool) -> bool {
     !x
 }
 
+fn is_zero(x: &Timestamp) -> bool {
+    *x == 0
+}
+
+/// The average of the given durations in days,
+/// or `None` if there are none.
+fn average_days(durations: impl Iterator<Item = Timestamp>) -> Option<f64> {
+    let (count, total) = durations.fold((0, 0), |(count, total), d| (count + 1, total + d));
+    if count == 0 {
+        None
+    } else {
+        Some(total as f64 / count as f64 / TICKS_PER_DAY as f64)
+    }
+}
+
 /// The outcome of a game event request.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum EventOutcome {
     /// No game state changes occurred, no need to re-render
     Nothing,
@@ -133,12 +185,103 @@ pub enum EventOutcome {
 
     EndOfMonth(MonthlyReport),
 
-    /// Alert the user with this message,
-    /// likely because the requested operation is invalid.
-    Alert(&'static str),
+    /// Alert the user with the message of this ID,
+    /// about something which they should be aware of.
+    Alert(Cow<'static, str>),
+}
+
+/// The reason why a task cannot be moved to a stage
+/// or assigned to a human.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MoveError {
+    /// the task is no longer where it was said to be
+    NotFound,
+    /// the task is already in the target stage
+    SameStage,
+    /// the task has to be fully specified first
+    NotSpecified,
+    /// the task has to be assigned to a developer first
+    NotAssigned,
+    /// the task has to be fully developed first
+    NotComplete,
+    /// the task is already assigned to this human
+    AlreadyAssigned,
+    /// nobody is assigned to the task
+    Unassigned,
+    /// the task was merged, there is nothing left to do
+    Merged,
+    /// the stages are not connected
+    Invalid,
+}
+
+impl MoveError {
+    /// The ID of the message explaining this to the player.
+    pub fn message_id(self) -> &'static str {
+        match self {
+            MoveError::NotFound => "alert.task_not_found",
+            MoveError::SameStage => "alert.same_stage",
+            MoveError::NotSpecified => "alert.not_specified",
+            MoveError::NotAssigned => "alert.not_assigned",
+            MoveError::NotComplete => "alert.not_complete",
+            MoveError::AlreadyAssigned => "alert.already_assigned",
+            MoveError::Unassigned => "alert.unassigned",
+            MoveError::Merged => "alert.task_merged",
+            MoveError::Invalid => "alert.invalid_move",
+        }
+    }
+}
+
+/// An error in applying a request to the game state.
+///
+/// These are reported to the player and the game carries on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameError {
+    /// the task is not where the request said it was,
+    /// likely because the board changed in the meantime
+    StaleTask(u32),
+    /// the human is not part of the team
+    UnknownHuman(u32),
+    /// the human never left the team
+    UnknownAlumnus(u32),
+    /// the request breaks the rules of the board
+    Move(MoveError),
+    /// the dropped data could not be read,
+    /// likely because it did not come from this game
+    Transfer(String),
+}
+
+impl GameError {
+    /// The ID of the message explaining this to the player.
+    pub fn message_id(&self) -> &'static str {
+        match self {
+            GameError::StaleTask(_) => "alert.task_not_found",
+            GameError::UnknownHuman(_) => "alert.unknown_human",
+            GameError::UnknownAlumnus(_) => "alert.unknown_alumnus",
+            GameError::Move(e) => e.message_id(),
+            GameError::Transfer(_) => "alert.bad_transfer",
+        }
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::StaleTask(id) => write!(f, "task #{} is no longer there", id),
+            GameError::UnknownHuman(id) => write!(f, "no human #{} in the team", id),
+            GameError::UnknownAlumnus(id) => write!(f, "no human #{} left the team", id),
+            GameError::Move(e) => write!(f, "illegal board action: {:?}", e),
+            GameError::Transfer(reason) => write!(f, "could not read dropped data: {}", reason),
+        }
+    }
+}
+
+impl From<MoveError> for GameError {
+    fn from(e: MoveError) -> Self {
+        GameError::Move(e)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MonthlyReport {
     /// month number
     pub month: u32,
@@ -152,9 +295,19 @@ pub struct MonthlyReport {
     pub bugs_fixed: u32,
     /// current project complexity
     pub complexity: u32,
+    /// number of dependencies behind their latest version
+    pub outdated_dependencies: usize,
+    /// average lead time of the tasks done this month, in days
+    pub lead_time: Option<f64>,
+    /// average cycle time of the tasks done this month, in days
+    pub cycle_time: Option<f64>,
+    /// average time under review of the tasks done this month, in days
+    pub review_time: Option<f64>,
+    /// what each human did this month, by display name
+    pub team: Vec<(String, HumanStats)>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FullReport {
     /// product name
     pub product_name: Rc<str>,
@@ -168,6 +321,10 @@ pub struct FullReport {
     pub tasks_done: usize,
     /// bugs fixed in total
     pub bugs_fixed: u32,
+    /// average lead time of all tasks done, in days
+    pub lead_time: Option<f64>,
+    /// average cycle time of all tasks done, in days
+    pub cycle_time: Option<f64>,
 }
 
 impl WorldState {
@@ -186,48 +343,33 @@ impl WorldState {
             bugs_fixed_in_total: 0,
             complexity: 25,
             score_linger_rate: 0,
-            tasks_backlog: vec![],
-            tasks_candidate: vec![],
-            tasks_progress: vec![],
-            tasks_review: vec![],
-            tasks_done: vec![],
-            humans: vec![GameHuman::new(0, "You", "#fff", 50)],
+            tasks: TaskBoard::default(),
+            archive: vec![],
+            humans: Roster::from(vec![GameHuman::new(0, "You", "#fff", 50)]),
+            dependencies: initial_dependencies(),
             task_ingest_rate: 3,
             tutorial: if tutorial { Some(0) } else { None },
+            tutorial_since: 0,
             ceo_message_delivered: false,
+            log: vec![],
+            samples: vec![],
+            history: History::default(),
         }
     }
 
-    /// Load the whole state of the game from local storage.
-    pub fn load_from_storage() -> Result<Option<Self>, JsValue> {
-        let local_storage = try_local_storage()?;
-
-        let save_item = local_storage.get_item("save")?;
-
-        if let Some(save_item) = save_item {
-            let world_state =
-                serde_json::from_str(&save_item).map_err(|e| JsValue::from(e.to_string()))?;
-
-            gloo_console::log!("Game successfully loaded from local storage");
-            Ok(Some(world_state))
-        } else {
-            Ok(None)
+    /// Load the whole state of the game from a save slot in local storage.
+    pub fn load_from_storage(slot: u32) -> Result<Option<Self>, SaveError> {
+        let world_state = saves::load_slot(slot)?;
+        if world_state.is_some() {
+            gloo_console::log!("Game successfully loaded from slot", slot);
         }
+        Ok(world_state)
     }
 
-    pub fn has_save_in_storage() -> Result<bool, JsValue> {
-        let local_storage = try_local_storage()?;
-
-        Ok(local_storage.get_item("save")?.is_some())
-    }
-
-    pub fn save(&self) -> Result<(), JsValue> {
-        let local_storage = try_local_storage()?;
-
-        let state_payload =
-            serde_json::to_string(&self).map_err(|e| JsValue::from(e.to_string()))?;
-        local_storage.set_item("save", &state_payload)?;
-        gloo_console::log!("Game saved to local storage");
+    /// Save the whole state of the game onto a save slot in local storage.
+    pub fn save(&self, slot: u32) -> Result<(), JsValue> {
+        saves::save_slot(slot, self)?;
+        gloo_console::log!("Game saved to slot", slot);
         Ok(())
     }
 
@@ -239,13 +381,16 @@ impl WorldState {
 
     /// Merge the given task upstream,
     /// applying changes to state as necessary.
-    pub fn merge_task(&mut self, task_transfer: &TaskTransfer) {
-        let mut task = self
-            .find_task_by_transfer_mut(&task_transfer)
-            .expect_throw("could not find task to merge");
+    pub fn merge_task(&mut self, task_transfer: &TaskTransfer) -> Result<(), GameError> {
+        let time = self.time;
+        let task = self
+            .tasks
+            .get_mut(task_transfer.id)
+            .ok_or(GameError::StaleTask(task_transfer.id))?;
 
         // unassign it from the human
         task.assigned = None;
+        task.record_transition(time);
         // remove progress
         task.progress = 0.;
 
@@ -253,6 +398,8 @@ impl WorldState {
         let kind = task.kind;
         let bugs = task.bugs;
         let difficulty = task.difficulty;
+        let migrates = task.migrates;
+        let task_id = task.id;
         let task_score = task.score * 1_000;
 
         // add score
@@ -277,8 +424,86 @@ impl WorldState {
             }
         }
 
+        // bring migrated dependency up to date
+        if let Some(dependency_id) = migrates {
+            if let Some(dependency) = self.dependencies.iter_mut().find(|d| d.id == dependency_id) {
+                dependency.migrate();
+            }
+        }
+
         // update score linger rate
         self.update_score_linger_rate();
+
+        self.log(
+            LogCategory::Task,
+            "log.task_merged",
+            &[
+                ("$TASK", task_label(task_id)),
+                ("$SCORE", format!("{:+}", task_score / 1_000)),
+            ],
+        );
+        if bugs > 0 {
+            self.log(
+                LogCategory::Bug,
+                "log.bugs_shipped",
+                &[("$TASK", task_label(task_id)), ("$N", bugs.to_string())],
+            );
+        }
+        Ok(())
+    }
+
+    /// Take a sample of the game's current metrics.
+    fn sample(&self) -> Sample {
+        let deadline_tasks = self.tasks.open().filter(|t| t.deadline.is_some()).count();
+
+        Sample {
+            time: self.time,
+            month: self.month,
+            backlog: self.tasks.count(StageId::Backlog) as u32,
+            candidate: self.tasks.count(StageId::Candidate) as u32,
+            progress: self.tasks.count(StageId::Progress) as u32,
+            review: self.tasks.count(StageId::Review) as u32,
+            done: self.tasks_done_in_total() as u32,
+            deadline_tasks: deadline_tasks as u32,
+            score: self.total_score / 1_000,
+            complexity: self.complexity,
+            linger_rate: self.score_linger_rate,
+        }
+    }
+
+    /// Record something which happened in the game log.
+    fn log(&mut self, category: LogCategory, id: &str, vars: &[(&str, String)]) {
+        let entry = LogEntry::new(self.month, self.time_in_month, category, id, vars);
+        event_log::push_entry(&mut self.log, entry);
+    }
+
+    /// Apply the changes of a major event to the world state.
+    fn apply_changes(&mut self, changes: &StateChanges) {
+        if *changes == StateChanges::default() {
+            return;
+        }
+
+        self.total_score = (self.total_score as i32)
+            .saturating_add(changes.score)
+            .max(0) as u32;
+        self.score_in_month += changes.score;
+        self.complexity = (self.complexity as i32)
+            .saturating_add(changes.complexity)
+            .max(0) as u32;
+        self.bugs = (self.bugs as i32).saturating_add(changes.bugs).max(0) as u32;
+        self.task_ingest_rate = (self.task_ingest_rate as i32)
+            .saturating_add(changes.task_ingest_rate)
+            .max(0) as u32;
+
+        if changes.score != 0 {
+            self.log(
+                LogCategory::Score,
+                "log.score_changed",
+                &[("$SCORE", format!("{:+}", changes.score / 1_000))],
+            );
+        }
+
+        self.update_score_linger_rate();
     }
 
     fn update_score_linger_rate(&mut self) {
@@ -294,256 +519,572 @@ impl WorldState {
     }
 
     pub fn human_of(&self, id: u32) -> Option<&GameHuman> {
-        self.humans
-            .binary_search_by_key(&id, |h| h.id)
-            .ok()
-            .map(|i| &self.humans[i])
+        self.humans.get(id)
     }
 
-    /// Apply the given request to the world state.
+    /// The description of the given task in the current locale.
+    pub fn task_description(&self, task: &GameTask) -> String {
+        let dependency = task
+            .migrates
+            .and_then(|id| self.dependencies.iter().find(|d| d.id == id));
+        match dependency {
+            Some(dependency) => tr_vars(
+                "task.migrate",
+                &[
+                    ("$DEPENDENCY", dependency.name.as_ref()),
+                    ("$LATEST", &dependency.latest.to_string()),
+                ],
+            ),
+            // descriptions which are not message IDs are shown as they are
+            None => tr(&task.description),
+        }
+    }
+
+    /// Apply the given request to the world state,
+    /// along with what changed in it.
     ///
-    /// Returns false if the request was invalid.
+    /// Returns an error if the request could not be applied,
+    /// in which case the state should be left as it was.
+    /// What changed is reported along with the error all the same,
+    /// so that copies of the state never fall out of step.
     pub fn apply_event(
         &mut self,
         event: EventBusRequest,
         reactor: &mut EventReactor,
-    ) -> EventOutcome {
+    ) -> Result<(EventOutcome, ChangeSet), (GameError, ChangeSet)> {
+        let before = Snapshot::of(self);
+        let outcome = self.handle_event(event, reactor);
+        let changes = before.changes(self);
+        match outcome {
+            Ok(outcome) => Ok((outcome, changes)),
+            Err(e) => Err((e, changes)),
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        event: EventBusRequest,
+        reactor: &mut EventReactor,
+    ) -> Result<EventOutcome, GameError> {
         // handle all state change requests here
         // (better move specific state operations to state module though)
-        match event {
-            EventBusRequest::MoveTask { task, to } => self.handle_move_task(task, to),
-            EventBusRequest::AssignTask { task, human_id } => self.assign_task(task, human_id),
+        // new board actions forget the undone ones,
+        // unless they were rejected
+        let outcome = match event {
+            EventBusRequest::MoveTask { task, to } => {
+                let outcome = self.handle_move_task(task, to)?;
+                self.history.clear_redo();
+                outcome
+            }
+            EventBusRequest::AssignTask { task, human_id } => {
+                let outcome = self.assign_task(task, human_id)?;
+                self.history.clear_redo();
+                outcome
+            }
+            EventBusRequest::UnassignTask { task } => {
+                let outcome = self.unassign_task(task)?;
+                self.history.clear_redo();
+                outcome
+            }
+            EventBusRequest::AssignReview { human_id } => match self.next_review(human_id) {
+                Some(task) => {
+                    let outcome = self.assign_task(task, human_id)?;
+                    self.history.clear_redo();
+                    outcome
+                }
+                None => EventOutcome::Alert("alert.nothing_to_review".into()),
+            },
+            EventBusRequest::LayOff { human_id } => self.lay_off(human_id)?,
+            EventBusRequest::Rehire { human_id } => self.rehire(human_id)?,
+            EventBusRequest::ReportError(e) => return Err(e),
+            EventBusRequest::Undo => self.undo()?,
+            EventBusRequest::Redo => self.redo()?,
             EventBusRequest::Tick => self.tick(reactor),
-            EventBusRequest::AdvanceTutorial => self.advance_tutorial(),
+            EventBusRequest::AdvanceTutorial => self
+                .tutorial_event(TutorialEvent::Acknowledged)
+                .unwrap_or(EventOutcome::Nothing),
             EventBusRequest::AddTask(task) => {
                 self.add_task(task);
                 EventOutcome::Update
             }
-            EventBusRequest::NextMonth => self.next_month(),
+            EventBusRequest::NextMonth => self.next_month(reactor),
             _ => EventOutcome::Nothing,
-        }
-    }
-
-    fn find_task_by_transfer(&self, transfer: &TaskTransfer) -> Option<&GameTask> {
-        let tasks = match transfer.from {
-            StageId::Backlog => &self.tasks_backlog,
-            StageId::Candidate => &self.tasks_candidate,
-            StageId::Progress => &self.tasks_progress,
-            StageId::Review => &self.tasks_review,
-            StageId::Done => &self.tasks_done,
         };
-
-        tasks.iter().find(|t| t.id == transfer.id)
+        Ok(outcome)
     }
 
-    fn find_task_by_transfer_mut(&mut self, transfer: &TaskTransfer) -> Option<&mut GameTask> {
-        let tasks = match transfer.from {
-            StageId::Backlog => &mut self.tasks_backlog,
-            StageId::Candidate => &mut self.tasks_candidate,
-            StageId::Progress => &mut self.tasks_progress,
-            StageId::Review => &mut self.tasks_review,
-            StageId::Done => &mut self.tasks_done,
-        };
-
-        tasks.iter_mut().find(|t| t.id == transfer.id)
+    fn move_task(&mut self, task: &TaskTransfer, to: StageId) -> Result<(), GameError> {
+        let time = self.time;
+        let task = self
+            .tasks
+            .move_to(task.id, to)
+            .ok_or(GameError::StaleTask(task.id))?;
+        task.record_transition(time);
+        Ok(())
     }
 
-    fn move_task(&mut self, task: &TaskTransfer, to: StageId) {
-        let task_list = match task.from {
-            StageId::Backlog => &mut self.tasks_backlog,
-            StageId::Candidate => &mut self.tasks_candidate,
-            StageId::Progress => &mut self.tasks_progress,
-            StageId::Review => &mut self.tasks_review,
-            StageId::Done => &mut self.tasks_done,
+    fn handle_move_task(
+        &mut self,
+        task: TaskTransfer,
+        to: StageId,
+    ) -> Result<EventOutcome, GameError> {
+        let task_id = task.id;
+        // the board knows where the task is, the transfer may be stale
+        let (from, progress) = self
+            .tasks
+            .get(task_id)
+            .map(|t| (t.stage, t.progress))
+            .ok_or(GameError::StaleTask(task_id))?;
+        let reviewer = self.move_task_checked(task, to)?;
+
+        let event = if to == StageId::Done {
+            TutorialEvent::TaskMerged(task_id)
+        } else {
+            TutorialEvent::TaskMoved(task_id, to)
         };
+        // merging is final, and so is what the tutorial did next
+        if let Some(outcome) = self.tutorial_event(event) {
+            return Ok(outcome);
+        }
+        if to != StageId::Done {
+            let action = BoardAction::MoveTask {
+                task_id,
+                from,
+                to,
+                progress,
+                reviewer,
+            };
+            self.history.record(self.time, action);
+        }
 
-        let index = task_list
-            .iter()
-            .position(|t| t.id == task.id)
-            .unwrap_throw();
-
-        // remove it from old list
-        let mut task = task_list.remove(index);
-        task.stage = to;
-
-        // place it on new list
-        let new_task_list = match to {
-            StageId::Backlog => &mut self.tasks_backlog,
-            StageId::Candidate => &mut self.tasks_candidate,
-            StageId::Progress => &mut self.tasks_progress,
-            StageId::Review => &mut self.tasks_review,
-            StageId::Done => &mut self.tasks_done,
-        };
+        if to == StageId::Progress && self.is_busy_elsewhere(task_id) {
+            return Ok(EventOutcome::Alert("alert.human_busy".into()));
+        }
 
-        new_task_list.push(task);
+        Ok(EventOutcome::Update)
     }
 
-    fn handle_move_task(&mut self, mut task: TaskTransfer, to: StageId) -> EventOutcome {
-        let game_task = self.find_task_by_transfer_mut(&task).unwrap_throw();
+    /// Check whether the given task can be moved to the given stage.
+    pub fn can_move(&self, task: &TaskTransfer, to: StageId) -> Result<(), MoveError> {
+        let game_task = self.tasks.get(task.id).ok_or(MoveError::NotFound)?;
 
         match (game_task.stage, to) {
+            (from, to) if from == to => Err(MoveError::SameStage),
             // unconditional:
-            // from backlog to candidate
-            (StageId::Backlog, StageId::Candidate) => {
-                self.move_task(&task, to);
-                EventOutcome::Update
-            }
-            // from in progress to candidate,
-            // dev progress is retained
-            (StageId::Progress, StageId::Candidate) => {
-                self.move_task(&task, to);
-                EventOutcome::Update
-            }
-            // from under review to in progress
-            (StageId::Review, StageId::Progress) => {
-                if game_task.bugs_found > 0 {
-                    game_task.progress = 0.66666;
-                }
-                self.move_task(&task, to);
-
-                EventOutcome::Update
-            }
-            // from under review to done
-            (StageId::Review, StageId::Done) => {
-                self.move_task(&task, to);
-                task.from = StageId::Done;
-                self.merge_task(&task);
-
-                // tutorial step
-                if let Some(6 | 7 | 8) = self.tutorial {
-                    self.tutorial = Some(8);
-                    return self.advance_tutorial();
-                }
-
-                EventOutcome::Update
-            }
+            // from backlog to candidate,
+            // from in progress to candidate (dev progress is retained),
+            // from under review to in progress or done
+            (StageId::Backlog, StageId::Candidate)
+            | (StageId::Progress, StageId::Candidate)
+            | (StageId::Review, StageId::Progress)
+            | (StageId::Review, StageId::Done) => Ok(()),
             // only if the task is fully specified
             // and assigned to a developer:
             // from candidate to in progress
             (StageId::Candidate, StageId::Progress) => {
                 if !game_task.is_specified() {
-                    return EventOutcome::Alert("The task is not fully specified yet!");
-                }
-                if game_task.assigned.is_none() {
-                    return EventOutcome::Alert(
-                        "The task needs to be assigned to a developer first!",
-                    );
-                }
-                // progress now means development progress
-                game_task.progress = 0.;
-                self.move_task(&task, to);
-
-                EventOutcome::Update
-            }
-            // only if fully developed:
-            // from in progress to under review
-            (StageId::Progress, StageId::Review) => {
-                if game_task.is_developed() {
-                    self.move_task(&task, to);
-
-                    // tutorial step 7
-                    if self.tutorial == Some(7) {
-                        return self.advance_tutorial();
-                    }
-
-                    EventOutcome::Update
+                    Err(MoveError::NotSpecified)
+                } else if game_task.assigned.is_none() {
+                    Err(MoveError::NotAssigned)
                 } else {
-                    EventOutcome::Alert("The task is not complete yet!")
+                    Ok(())
                 }
             }
             // only if fully developed:
-            // from in progress to done
-            (StageId::Progress, StageId::Done) => {
+            // from in progress to under review or done
+            (StageId::Progress, StageId::Review) | (StageId::Progress, StageId::Done) => {
                 if game_task.is_developed() {
-                    self.move_task(&task, to);
-                    task.from = StageId::Done;
-                    self.merge_task(&task);
-
-                    // tutorial step
-                    if let Some(6 | 7 | 8) = self.tutorial {
-                        self.tutorial = Some(8);
-                        return self.advance_tutorial();
-                    }
-
-                    EventOutcome::Update
+                    Ok(())
                 } else {
-                    EventOutcome::Alert("The task is not complete yet!")
+                    Err(MoveError::NotComplete)
                 }
             }
             // only if not yet specified:
             // from candidate to backlog
-            (StageId::Candidate, StageId::Backlog) if !game_task.is_specified() => {
-                self.move_task(&task, to);
-                EventOutcome::Update
+            (StageId::Candidate, StageId::Backlog) if !game_task.is_specified() => Ok(()),
+            // not a valid move
+            (_, _) => Err(MoveError::Invalid),
+        }
+    }
+
+    /// Check whether the given task can be assigned to the given human.
+    pub fn can_assign(&self, task: &TaskTransfer, human_id: u32) -> Result<(), MoveError> {
+        let game_task = self.tasks.get(task.id).ok_or(MoveError::NotFound)?;
+
+        if game_task.stage == StageId::Done {
+            Err(MoveError::Merged)
+        } else if game_task.assigned == Some(human_id) {
+            Err(MoveError::AlreadyAssigned)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check whether the given task can be left without anyone assigned.
+    pub fn can_unassign(&self, task: &TaskTransfer) -> Result<(), MoveError> {
+        let game_task = self.tasks.get(task.id).ok_or(MoveError::NotFound)?;
+
+        if game_task.stage == StageId::Done {
+            Err(MoveError::Merged)
+        } else if game_task.assigned.is_none() {
+            Err(MoveError::Unassigned)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Move a task if allowed,
+    /// returning the human credited with a review if the move finished one.
+    fn move_task_checked(
+        &mut self,
+        mut task: TaskTransfer,
+        to: StageId,
+    ) -> Result<Option<u32>, GameError> {
+        self.can_move(&task, to)?;
+
+        let game_task = self
+            .tasks
+            .get_mut(task.id)
+            .ok_or(GameError::StaleTask(task.id))?;
+
+        match (game_task.stage, to) {
+            // bugs found in review are fixed in development
+            (StageId::Review, StageId::Progress) => {
+                if game_task.bugs_found > 0 {
+                    game_task.progress = 0.66666;
+                }
+                let reviewer = game_task.assigned;
+                self.move_task(&task, to)?;
+                Ok(self.review_done(reviewer))
+            }
+            (StageId::Review, StageId::Done) => {
+                let reviewer = game_task.assigned;
+                self.move_task(&task, to)?;
+                let reviewer = self.review_done(reviewer);
+                task.from = StageId::Done;
+                self.merge_task(&task)?;
+                Ok(reviewer)
+            }
+            (StageId::Progress, StageId::Done) => {
+                self.move_task(&task, to)?;
+                task.from = StageId::Done;
+                self.merge_task(&task)?;
+                Ok(None)
+            }
+            // progress now means development progress
+            (StageId::Candidate, StageId::Progress) => {
+                game_task.progress = 0.;
+                self.move_task(&task, to)?;
+                Ok(None)
             }
             (_, _) => {
-                // not a valid move
-                EventOutcome::Alert("Invalid task move!")
+                self.move_task(&task, to)?;
+                Ok(None)
             }
         }
     }
 
-    fn assign_task(&mut self, task: TaskTransfer, human_id: u32) -> EventOutcome {
-        let task = self.find_task_by_transfer_mut(&task).unwrap_throw();
+    /// The oldest task under review which the given human could pick up:
+    /// nobody else is reviewing it and they did not develop it.
+    fn next_review(&self, human_id: u32) -> Option<TaskTransfer> {
+        self.tasks
+            .in_stage(StageId::Review)
+            .filter(|t| t.assigned.is_none() || t.assigned == t.developed_by)
+            .filter(|t| t.developed_by != Some(human_id))
+            .min_by_key(|t| t.entered(StageId::Review).unwrap_or(t.created))
+            .map(|t| TaskTransfer {
+                id: t.id,
+                kind: t.kind,
+                from: StageId::Review,
+                progress: t.progress,
+            })
+    }
+
+    /// Count a review done by the human who was assigned to a task leaving review,
+    /// returning who was credited with it.
+    fn review_done(&mut self, reviewer: Option<u32>) -> Option<u32> {
+        let human = self.humans.member_mut(reviewer?)?;
+        human.stats.reviews_done += 1;
+        Some(human.id)
+    }
+
+    fn assign_task(
+        &mut self,
+        task: TaskTransfer,
+        human_id: u32,
+    ) -> Result<EventOutcome, GameError> {
+        if !self.humans.is_member(human_id) {
+            return Err(GameError::UnknownHuman(human_id));
+        }
+        match self.can_assign(&task, human_id) {
+            Ok(()) => {}
+            // already assigned to this human, do nothing
+            Err(MoveError::AlreadyAssigned) => return Ok(EventOutcome::Nothing),
+            Err(e) => return Err(e.into()),
+        }
+
+        self.set_assignee(&task, Some(human_id))
+    }
+
+    fn unassign_task(&mut self, task: TaskTransfer) -> Result<EventOutcome, GameError> {
+        match self.can_unassign(&task) {
+            Ok(()) => {}
+            // nobody to unassign, do nothing
+            Err(MoveError::Unassigned) => return Ok(EventOutcome::Nothing),
+            Err(e) => return Err(e.into()),
+        }
+
+        self.set_assignee(&task, None)
+    }
+
+    /// Change who is assigned to a task.
+    ///
+    /// A task under development which is handed over to another developer
+    /// loses part of its progress,
+    /// as they need to catch up with what was done so far.
+    fn set_assignee(
+        &mut self,
+        task: &TaskTransfer,
+        human_id: Option<u32>,
+    ) -> Result<EventOutcome, GameError> {
+        let time = self.time;
+        let task = self
+            .tasks
+            .get_mut(task.id)
+            .ok_or(GameError::StaleTask(task.id))?;
+
+        let action = BoardAction::AssignTask {
+            task_id: task.id,
+            stage: task.stage,
+            previous: task.assigned,
+            human_id,
+            progress: task.progress,
+        };
+
+        let handover = human_id.is_some()
+            && task.stage == StageId::Progress
+            && !task.is_developed()
+            && task.progress > 0.
+            && matches!(task.developed_by, Some(developer) if Some(developer) != human_id);
+        if handover {
+            task.progress *= HANDOVER_PROGRESS_KEPT;
+        }
+
+        task.assigned = human_id;
+        task.record_transition(time);
+        let task_id = task.id;
+
+        self.history.record(self.time, action);
+
+        let outcome = if handover {
+            EventOutcome::Alert("alert.handover".into())
+        } else if self.is_busy_elsewhere(task_id) {
+            EventOutcome::Alert("alert.human_busy".into())
+        } else {
+            EventOutcome::Update
+        };
+        Ok(outcome)
+    }
+
+    /// Whether the developer assigned to the given task under development
+    /// is also assigned to other tasks under development,
+    /// which they can only work on one at a time.
+    fn is_busy_elsewhere(&self, task_id: u32) -> bool {
+        let pending = |t: &&GameTask| !t.is_developed() && t.assigned.is_some();
+        let human_id = match self
+            .tasks
+            .get(task_id)
+            .filter(|t| t.stage == StageId::Progress)
+            .filter(pending)
+        {
+            Some(task) => task.assigned,
+            None => return false,
+        };
+        self.tasks
+            .in_stage(StageId::Progress)
+            .filter(pending)
+            .any(|t| t.id != task_id && t.assigned == human_id)
+    }
+
+    /// Find a task by its ID in the given stage.
+    fn find_task_mut(&mut self, id: u32, stage: StageId) -> Option<&mut GameTask> {
+        self.tasks.get_mut(id).filter(|t| t.stage == stage)
+    }
 
-        if let Some(assigned_human) = task.assigned {
-            if assigned_human == human_id {
-                // already assigned to this human, do nothing
-                return EventOutcome::Nothing;
+    /// Undo the last board action of the player, if still possible.
+    fn undo(&mut self) -> Result<EventOutcome, GameError> {
+        let now = self.time;
+        let (time, action) = match self.history.pop_undo() {
+            Some(entry) => entry,
+            None => return Ok(EventOutcome::Nothing),
+        };
+
+        match action {
+            BoardAction::MoveTask {
+                task_id,
+                from,
+                to,
+                progress,
+                reviewer,
+            } => {
+                let transfer = match self.find_task_mut(task_id, to) {
+                    Some(task) => TaskTransfer {
+                        id: task.id,
+                        kind: task.kind,
+                        from: to,
+                        progress: task.progress,
+                    },
+                    None => return Ok(EventOutcome::Alert("alert.cannot_undo".into())),
+                };
+                if let Err(e) = self.move_task(&transfer, from) {
+                    // the action can still be undone later
+                    self.history.record(time, action);
+                    return Err(e);
+                }
+                if let Some(task) = self.find_task_mut(task_id, from) {
+                    task.progress = progress;
+                }
+                // the review is done again on redo
+                if let Some(human) = self.humans.iter_mut().find(|h| Some(h.id) == reviewer) {
+                    human.stats.reviews_done = human.stats.reviews_done.saturating_sub(1);
+                }
+            }
+            BoardAction::AssignTask {
+                task_id,
+                stage,
+                previous,
+                human_id,
+                progress,
+            } => {
+                // humans who left the team cannot be assigned again
+                if matches!(previous, Some(id) if !self.humans.is_member(id)) {
+                    return Ok(EventOutcome::Alert("alert.cannot_undo".into()));
+                }
+                match self.find_task_mut(task_id, stage) {
+                    Some(task) if task.assigned == human_id => {
+                        task.assigned = previous;
+                        task.progress = progress;
+                        task.record_transition(now);
+                    }
+                    _ => return Ok(EventOutcome::Alert("alert.cannot_undo".into())),
+                }
             }
         }
 
-        // assign at this task
-        task.assigned = Some(human_id);
+        self.history.push_redo(time, action);
+        Ok(EventOutcome::Update)
+    }
 
-        EventOutcome::Update
+    /// Perform again the last board action undone by the player.
+    fn redo(&mut self) -> Result<EventOutcome, GameError> {
+        let (time, action) = match self.history.pop_redo() {
+            Some(entry) => entry,
+            None => return Ok(EventOutcome::Nothing),
+        };
+
+        let (task_id, stage) = match action {
+            BoardAction::MoveTask { task_id, from, .. } => (task_id, from),
+            BoardAction::AssignTask { task_id, stage, .. } => (task_id, stage),
+        };
+        let transfer = match self.tasks.get(task_id).filter(|t| t.stage == stage) {
+            Some(task) => TaskTransfer {
+                id: task.id,
+                kind: task.kind,
+                from: stage,
+                progress: task.progress,
+            },
+            None => return Ok(EventOutcome::Alert("alert.cannot_redo".into())),
+        };
+
+        let outcome = match action {
+            BoardAction::MoveTask { to, .. } => self.handle_move_task(transfer, to),
+            BoardAction::AssignTask {
+                human_id: Some(human_id),
+                ..
+            } => self.assign_task(transfer, human_id),
+            BoardAction::AssignTask { human_id: None, .. } => self.unassign_task(transfer),
+        };
+        if outcome.is_err() {
+            // a rejected redo can be tried again
+            self.history.push_redo(time, action);
+        }
+        outcome
     }
 
     fn tick(&mut self, reactor: &mut EventReactor) -> EventOutcome {
         // move time forward
         self.time += 1;
+        self.history.expire(self.time);
         self.time_in_month += 1;
 
+        // sample metrics at the end of each day
+        if self.time_in_month % TICKS_PER_SAMPLE == 0 {
+            let sample = self.sample();
+            analytics::push_sample(&mut self.samples, sample);
+        }
+
         let mut worked = HashSet::new();
 
         let mut score_penalty = 0;
+        let mut missed = vec![];
         let time = self.time;
         // detect unfulfilled tasks
-        for task in self.all_tasks_iter_mut() {
+        self.tasks.for_each_open_mut(|task| {
             if let Some(deadline) = task.deadline {
                 if deadline < time {
                     // apply penalty
                     score_penalty += task.score * 1_000;
+                    missed.push((task.id, task.score));
 
                     // reset deadline
                     task.deadline = None;
+                    return true;
                 }
             }
-        }
+            false
+        });
         if score_penalty > 0 {
             self.total_score = self.total_score.saturating_sub(score_penalty as u32);
             self.score_in_month -= score_penalty as i32;
         }
+        for (task_id, score) in missed {
+            self.log(
+                LogCategory::Score,
+                "log.deadline_missed",
+                &[
+                    ("$TASK", task_label(task_id)),
+                    ("$SCORE", format!("{}", -score)),
+                ],
+            );
+        }
+
+        let (month, time_in_month) = (self.month, self.time_in_month);
 
         // apply human work (development)
-        for task in &mut self.tasks_progress {
+        let mut developed = None;
+        self.tasks.for_each_mut(StageId::Progress, |task| {
             if task.is_developed() {
-                continue;
+                return false;
             }
 
             if let Some(human_id) = task.assigned {
                 if worked.contains(&human_id) {
                     // this human already worked
-                    continue;
+                    return false;
                 }
 
-                let human = self.humans.get_mut(human_id as usize).unwrap_throw();
+                let human = match self.humans.member_mut(human_id) {
+                    Some(human) => human,
+                    // assignments are dropped when humans leave the team
+                    None => return false,
+                };
 
                 // do progress on task
                 let added_progress = 0.005
                     + (5 + human.experience) as f64
                         / (task.difficulty * 70 + self.complexity * 60) as f64;
                 let complete = task.add_progress(added_progress);
+                task.developed_by = Some(human.id);
                 human.status = HumanStatus::Coding;
 
                 // as this human worked on the task,
@@ -553,13 +1094,19 @@ impl WorldState {
                 // roll for adding a bug
                 if reactor.human_introduced_bug(human, task, self.complexity) {
                     task.bugs += 1;
-
-                    // gloo_console::debug!("Bug introduced in", format!("T{}", task.id));
+                    human.stats.bugs_introduced += 1;
+
+                    let entry = LogEntry::new(
+                        month,
+                        time_in_month,
+                        LogCategory::Bug,
+                        "log.bug_introduced",
+                        &[("$TASK", task_label(task.id))],
+                    );
+                    event_log::push_entry(&mut self.log, entry);
                 }
 
                 if complete {
-                    // record developed_by
-                    task.developed_by = Some(human.id);
                     // record bugs fixed
                     self.bugs_fixed_in_month += task.bugs_found;
                     self.bugs_fixed_in_total += task.bugs_found;
@@ -568,33 +1115,36 @@ impl WorldState {
                     task.bugs_found = 0;
 
                     // add experience to human
-                    human.experience = (human.experience + task.difficulty / 4).min(150);
+                    let experience = (human.experience + task.difficulty / 4).min(150);
+                    human.stats.experience_gained += experience - human.experience;
+                    human.experience = experience;
+                    human.stats.tasks_developed += 1;
 
-                    if self.tutorial == Some(5) {
-                        // ensure bug in task,
-                        task.bugs = task.bugs.max(1);
-                        // increase experience of You
-                        // (to make bug a bit easier to find)
-                        self.humans[0].experience += 2;
-                        // advance tutorial
-                        return self.advance_tutorial();
-                    }
+                    developed = developed.or(Some(task.id));
                 }
+                return true;
+            }
+            false
+        });
+        if let Some(id) = developed {
+            if let Some(outcome) = self.tutorial_event(TutorialEvent::TaskDeveloped(id)) {
+                return outcome;
             }
         }
 
         // traverse the tasks again for specification
-        for task in &mut self.tasks_candidate {
+        let mut specified = None;
+        self.tasks.for_each_mut(StageId::Candidate, |task| {
             // if You already worked,
             // then you cannot work on task specification
             if worked.contains(&0) {
-                break;
+                return false;
             }
             // work on specification
             if task.is_specified() {
-                continue;
+                return false;
             }
-            let you = self.humans.get_mut(0).unwrap_throw();
+            let you = self.humans.member_mut(0).unwrap_throw();
 
             // do writing progress on task
             let added_progress =
@@ -604,67 +1154,95 @@ impl WorldState {
 
             worked.insert(0);
 
-            if complete && (self.tutorial == Some(4) || self.tutorial == Some(10)) {
-                // advance tutorial
-                return self.advance_tutorial();
+            if complete {
+                specified = Some(task.id);
+            }
+            true
+        });
+        if let Some(id) = specified {
+            if let Some(outcome) = self.tutorial_event(TutorialEvent::TaskSpecified(id)) {
+                return outcome;
             }
         }
 
         // traverse tasks under review
-        for task in &mut self.tasks_review {
-            if task.stage != StageId::Review {
-                continue;
-            }
-
+        let mut bug_found = None;
+        self.tasks.for_each_mut(StageId::Review, |task| {
             if let Some(human_id) = task.assigned {
                 if worked.contains(&human_id) {
                     // this human already worked
-                    continue;
+                    return false;
                 }
 
-                let human = self.humans.get_mut(human_id as usize).unwrap_throw();
+                let human = match self.humans.member_mut(human_id) {
+                    Some(human) => human,
+                    // assignments are dropped when humans leave the team
+                    None => return false,
+                };
 
                 // review (detect bugs)
                 human.status = HumanStatus::Reviewing;
-                if reactor.human_detected_bug(human, task, self.complexity) {
+                let found = reactor.human_detected_bug(human, task, self.complexity);
+                if found {
                     task.bugs_found += 1;
-
-                    // tutorial step when reviewing the first task
-                    if self.tutorial == Some(6) {
-                        return self.advance_tutorial();
-                    }
+                    human.stats.bugs_caught += 1;
+                    bug_found = bug_found.or(Some(task.id));
+
+                    let entry = LogEntry::new(
+                        month,
+                        time_in_month,
+                        LogCategory::Bug,
+                        "log.bug_found",
+                        &[
+                            ("$NAME", human.display_name().to_string()),
+                            ("$TASK", task_label(task.id)),
+                        ],
+                    );
+                    event_log::push_entry(&mut self.log, entry);
                 }
 
                 // as this human worked on the task,
                 // they cannot work on other things
                 worked.insert(human_id);
+                return found;
+            }
+            false
+        });
+        if let Some(id) = bug_found {
+            if let Some(outcome) = self.tutorial_event(TutorialEvent::BugFound(id)) {
+                return outcome;
             }
         }
 
-        if !worked.contains(&0) && self.tutorial.filter(|&phase| phase < 12).is_none() {
+        let ingestion = self
+            .tutorial
+            .and_then(tutorial::step)
+            .map(|step| step.ingestion)
+            .unwrap_or(true);
+        if !worked.contains(&0) && ingestion {
             // You are idle,
             // so you will think about tasks to do
 
             // roll for ingestion of new task
             // based on ingestion rate and experience of You
-            let you_experience = self.humans[0].experience;
+            let you_experience = self.humans.member(0).unwrap_throw().experience;
 
             if let Some(new_task) = reactor.ingest_task(
                 you_experience,
                 self.bugs,
                 self.complexity,
                 self.task_ingest_rate,
-                self.tasks_backlog.len(),
+                self.tasks.count(StageId::Backlog),
             ) {
-                let id = self.add_task(new_task);
-                gloo_console::debug!("Task", id, "ingested");
+                self.add_task(new_task);
             }
         }
 
         // update status of humans who did not work
-        for human in &mut self.humans {
+        for human in self.humans.team_mut() {
             if !worked.contains(&human.id) {
                 human.status = HumanStatus::Idle;
+                human.stats.idle_ticks += 1;
             }
         }
 
@@ -682,71 +1260,86 @@ impl WorldState {
             return EventOutcome::EndOfMonth(report);
         }
 
-        // advance tutorial to end step if close to end of month
-        if self.time_in_month >= TICKS_PER_MONTH - TICKS_PER_MONTH / 16
-            && self.tutorial.filter(|&phase| phase < 13).is_some()
-        {
-            self.tutorial = Some(12);
-            return self.advance_tutorial();
+        // advance tutorial on time passing
+        let tutorial_event = if self.time_in_month >= TICKS_PER_MONTH - TICKS_PER_MONTH / 16 {
+            TutorialEvent::NearEndOfMonth
+        } else {
+            TutorialEvent::Tick
+        };
+        if let Some(outcome) = self.tutorial_event(tutorial_event) {
+            return outcome;
         }
 
         if (self.time % TICKS_PER_MAJOR_TICK) == 0 && self.tutorial.is_none() {
             // roll for random events
-            match reactor.major_event(&self) {
-                Some(GameEvent::RandomReport(report_id)) => {
-                    // open modal with some useless message
-                    let message = Message::random_report(report_id);
-                    return EventOutcome::OpenMessage(message);
-                }
-                Some(GameEvent::BugReported(task)) => {
-                    // add bug task
-                    self.add_task(task);
-                    // deduct score
-                    self.score_in_month -= 2_000;
-                    self.total_score = self.total_score.saturating_sub(2_000);
-
-                    // open modal with a bug message
-                    return EventOutcome::OpenMessage(Message::bug_reported());
-                }
+            if let Some(MajorEvent { event, changes }) = reactor.major_event(self) {
+                self.apply_changes(&changes);
 
-                Some(GameEvent::ExtraTechnicalDebt {
-                    message: _,
-                    extra_complexity,
-                }) => {
-                    self.complexity += extra_complexity;
-                    self.update_score_linger_rate();
-                    let message = Message::extra_technical_debt();
-                    return EventOutcome::OpenMessage(message);
-                }
+                match event {
+                    GameEvent::RandomReport(message) => {
+                        // open modal with some useless message
+                        return EventOutcome::OpenMessage(message);
+                    }
+                    GameEvent::BugReported { message, task } => {
+                        // add bug task
+                        self.add_task(task);
+                        self.log(LogCategory::Event, "log.bug_reported", &[]);
 
-                Some(GameEvent::MajorFeatureRequested { message: _, tasks }) => {
-                    self.add_tasks(tasks);
+                        // open modal with a bug message
+                        return EventOutcome::OpenMessage(message);
+                    }
 
-                    // open modal with a major feature request message
-                    return EventOutcome::OpenMessage(Message::feature_requested());
-                }
-                Some(GameEvent::HumanQuit(human_id)) => {
-                    // mark human as quit
-                    let human = self
-                        .humans
-                        .iter_mut()
-                        .find(|human| human.id == human_id)
-                        .unwrap();
-                    human.quit = true;
-                    let human_name = human.name.clone();
-                    let human_id = human.id;
-                    // unassign tasks everywhere
-                    for t in self.all_tasks_iter_mut() {
-                        if t.assigned == Some(human_id) {
-                            t.assigned = None;
+                    GameEvent::ExtraTechnicalDebt {
+                        message,
+                        extra_complexity,
+                        migration,
+                    } => {
+                        self.complexity += extra_complexity;
+                        self.update_score_linger_rate();
+                        self.log(
+                            LogCategory::Event,
+                            "log.dependency_trouble",
+                            &[("$N", extra_complexity.to_string())],
+                        );
+
+                        // add a migration chore,
+                        // unless one is already pending
+                        let pending = self
+                            .tasks
+                            .open()
+                            .any(|t| t.migrates.is_some() && t.migrates == migration.migrates);
+                        if !pending {
+                            self.add_task(migration);
                         }
+
+                        return EventOutcome::OpenMessage(message);
                     }
 
-                    // report by name
-                    return EventOutcome::OpenMessage(Message::human_quit(human_name));
-                }
-                None => {
-                    // do nothing
+                    GameEvent::MajorFeatureRequested { message, tasks } => {
+                        self.add_tasks(tasks);
+                        self.log(LogCategory::Event, "log.feature_requested", &[]);
+
+                        // open modal with a major feature request message
+                        return EventOutcome::OpenMessage(message);
+                    }
+                    GameEvent::HumanQuit { message, human_id } => {
+                        if let Some(name) = self.leave_team(human_id) {
+                            self.log(LogCategory::Team, "log.human_quit", &[("$NAME", name)]);
+                        }
+
+                        // a contractor fills in until the end of the month
+                        let month = self.month;
+                        let contractor = self.humans.hire(|id| reactor.new_contractor(id, month));
+                        let name = contractor.name.to_string();
+                        self.log(
+                            LogCategory::Team,
+                            "log.contractor_joined",
+                            &[("$NAME", name)],
+                        );
+
+                        // report by name
+                        return EventOutcome::OpenMessage(message);
+                    }
                 }
             }
 
@@ -756,6 +1349,14 @@ impl WorldState {
 
                 self.score_in_month -= damage as i32;
                 self.total_score = self.total_score.saturating_sub(damage);
+
+                if damage >= 1_000 {
+                    self.log(
+                        LogCategory::Score,
+                        "log.score_damage",
+                        &[("$SCORE", (damage / 1_000).to_string())],
+                    );
+                }
             }
         }
 
@@ -763,7 +1364,7 @@ impl WorldState {
     }
 
     /// Advance to the next month
-    fn next_month(&mut self) -> EventOutcome {
+    fn next_month(&mut self, reactor: &mut EventReactor) -> EventOutcome {
         // safe-guard: ignore request if month is not over
         if self.time_in_month < TICKS_PER_MONTH {
             return EventOutcome::Nothing;
@@ -779,35 +1380,101 @@ impl WorldState {
         // reset bugs fixed in month
         self.bugs_fixed_in_month = 0;
 
+        // reset human statistics
+        for human in self.humans.iter_mut() {
+            human.stats = HumanStats::default();
+        }
+
+        // contractors leave once their contract is over
+        let month = self.month;
+        let contract_over: Vec<u32> = self
+            .humans
+            .team()
+            .filter(|h| matches!(h.contract_until, Some(until) if until < month))
+            .map(|h| h.id)
+            .collect();
+        for human_id in contract_over {
+            if let Some(name) = self.leave_team(human_id) {
+                self.log(LogCategory::Team, "log.contract_ended", &[("$NAME", name)]);
+            }
+        }
+
+        self.log(
+            LogCategory::Event,
+            "log.new_month",
+            &[("$MONTH", self.month.to_string())],
+        );
+
         // update task ingestion rate
         self.task_ingest_rate += 1;
 
-        // hide tasks done
-        for t in &mut self.tasks_done {
-            t.visible = false;
-        }
+        // dependencies age as new versions come out
+        reactor.release_dependencies(&mut self.dependencies, self.time);
+
+        // archive tasks done, they are no longer shown
+        let done = self.tasks.take_stage(StageId::Done);
+        archive::archive(&mut self.archive, done, self.time, self.month - 1);
 
         // reset tutorial
         if self.tutorial.is_some() {
             self.tutorial = None;
 
             // remove onboard guy
-            if let Some(guy) = self.humans.iter_mut().find(|h| h.name == "Guy") {
-                guy.quit = true;
-                let guy_id = guy.id;
-
-                // unassign tasks everywhere
-                for t in self.all_tasks_iter_mut() {
-                    if t.assigned == Some(guy_id) {
-                        t.assigned = None;
-                    }
-                }
+            let guy_id = self.humans.team().find(|h| h.name == "Guy").map(|h| h.id);
+            if let Some(guy_id) = guy_id {
+                self.leave_team(guy_id);
             }
         }
 
         EventOutcome::Update
     }
 
+    /// Take a human out of the team,
+    /// leaving the tasks assigned to them without anyone assigned.
+    ///
+    /// Returns the name of the human who left,
+    /// or `None` if they were not in the team.
+    fn leave_team(&mut self, human_id: u32) -> Option<String> {
+        let name = self.humans.leave(human_id)?.display_name().to_string();
+
+        let time = self.time;
+        self.tasks.for_each_open_mut(|t| {
+            if t.assigned != Some(human_id) {
+                return false;
+            }
+            t.assigned = None;
+            t.record_transition(time);
+            true
+        });
+        Some(name)
+    }
+
+    /// Let a human go from the team.
+    /// You cannot let yourself go.
+    fn lay_off(&mut self, human_id: u32) -> Result<EventOutcome, GameError> {
+        if human_id == 0 {
+            return Ok(EventOutcome::Alert("alert.cannot_lay_off".into()));
+        }
+        let name = self
+            .leave_team(human_id)
+            .ok_or(GameError::UnknownHuman(human_id))?;
+        self.log(LogCategory::Team, "log.human_laid_off", &[("$NAME", name)]);
+        Ok(EventOutcome::Update)
+    }
+
+    /// Bring a human who left back into the team,
+    /// as a permanent member even if they were a contractor.
+    fn rehire(&mut self, human_id: u32) -> Result<EventOutcome, GameError> {
+        let human = self
+            .humans
+            .rehire(human_id)
+            .ok_or(GameError::UnknownAlumnus(human_id))?;
+        human.contract_until = None;
+        let name = human.display_name().to_string();
+        self.log(LogCategory::Team, "log.human_returned", &[("$NAME", name)]);
+        Ok(EventOutcome::Update)
+    }
+
     fn start_of_month(&mut self, reactor: &mut EventReactor) -> Option<EventOutcome> {
         if self.tutorial.is_some() {
             // do nothing if tutorial is active
@@ -817,7 +1484,7 @@ impl WorldState {
         // ingest a bunch of important tasks at once
         self.add_tasks(reactor.ingest_important_tasks(self.month, self.task_ingest_rate));
 
-        let humans_count = self.humans.iter().filter(|h| !h.quit).count();
+        let humans_count = self.humans.team_size();
 
         // identify win condition
         if !self.ceo_message_delivered
@@ -836,12 +1503,26 @@ impl WorldState {
         let expected_humans = (1 + (self.month + 3) / 6).min(10);
 
         if expected_humans as usize > humans_count {
-            // introduce a new human
-            let new_human = reactor.new_human(self.next_human_id(), self.month);
+            // someone who left before may come back
+            let returning = reactor
+                .returning_human(self.humans.alumni())
+                .and_then(|id| self.humans.rehire(id));
+            if let Some(human) = returning {
+                human.contract_until = None;
+                let message = Message::human_returned(human);
+                let name = human.name.to_string();
+                self.log(LogCategory::Team, "log.human_returned", &[("$NAME", name)]);
+
+                return Some(EventOutcome::OpenMessage(message));
+            }
 
-            let message = Message::new_human(&new_human);
+            // introduce a new human
+            let month = self.month;
+            let new_human = self.humans.hire(|id| reactor.new_human(id, month));
 
-            self.humans.push(new_human);
+            let message = Message::new_human(new_human);
+            let name = new_human.name.to_string();
+            self.log(LogCategory::Team, "log.human_joined", &[("$NAME", name)]);
 
             return Some(EventOutcome::OpenMessage(message));
         }
@@ -849,19 +1530,65 @@ impl WorldState {
         Some(EventOutcome::Update)
     }
 
-    fn advance_tutorial(&mut self) -> EventOutcome {
-        if let Some(phase) = &mut self.tutorial {
-            *phase += 1;
+    /// Notify the tutorial of something that happened in the game.
+    ///
+    /// Returns the outcome of entering the next tutorial step,
+    /// or `None` if the tutorial did not advance.
+    fn tutorial_event(&mut self, event: TutorialEvent) -> Option<EventOutcome> {
+        let current = self.tutorial?;
+        let elapsed = self.time.saturating_sub(self.tutorial_since);
+        let next = tutorial::next_step(current, event, elapsed)?;
+        let step = tutorial::step(next)?;
+
+        self.tutorial = Some(next);
+        self.tutorial_since = self.time;
+
+        for effect in step.effects {
+            self.apply_tutorial_effect(effect, event);
+        }
 
-            if *phase == 11 {
-                // add onboard guy to team
-                let guy = GameHuman::new(1, "Guy", "#333", 126);
-                self.humans.push(guy);
-            }
+        Some(match step.message {
+            Some(message) => EventOutcome::OpenMessage(Message::Tutorial(message)),
+            None => EventOutcome::Update,
+        })
+    }
 
-            EventOutcome::OpenMessage(Message::Tutorial(*phase))
-        } else {
-            EventOutcome::Nothing
+    fn apply_tutorial_effect(&mut self, effect: &TutorialEffect, event: TutorialEvent) {
+        match *effect {
+            TutorialEffect::AddTask {
+                description,
+                kind,
+                score,
+                difficulty,
+            } => {
+                self.add_task(GameTaskBuilder::new(description, kind, score, difficulty));
+            }
+            TutorialEffect::AddHuman {
+                name,
+                color,
+                experience,
+            } => {
+                self.humans
+                    .hire(|id| GameHuman::new(id, name, color, experience));
+                self.log(
+                    LogCategory::Team,
+                    "log.human_joined",
+                    &[("$NAME", name.to_string())],
+                );
+            }
+            TutorialEffect::PlantBug => {
+                if let Some(id) = event.task_id() {
+                    if let Some(task) = self.tasks.get_mut(id).filter(|t| t.stage != StageId::Done)
+                    {
+                        task.bugs = task.bugs.max(1);
+                    }
+                }
+            }
+            TutorialEffect::AddExperience { human_id, amount } => {
+                if let Some(human) = self.humans.member_mut(human_id) {
+                    human.experience += amount;
+                }
+            }
         }
     }
 
@@ -869,27 +1596,39 @@ impl WorldState {
         self.next_task_id
     }
 
-    pub fn next_human_id(&self) -> u32 {
-        self.humans.last().map(|t| t.id + 1).unwrap_or(0)
-    }
-
-    /// get an iterator for all non-merged tasks, mutable
-    fn all_tasks_iter_mut(&mut self) -> impl Iterator<Item = &mut GameTask> {
-        self.tasks_backlog
-            .iter_mut()
-            .chain(self.tasks_candidate.iter_mut())
-            .chain(self.tasks_progress.iter_mut())
-            .chain(self.tasks_review.iter_mut())
-    }
-
     fn month_report(&self) -> MonthlyReport {
+        let timings: Vec<_> = self
+            .tasks
+            .in_stage(StageId::Done)
+            .map(|t| t.timings(self.time))
+            .collect();
+        let cycle_times: Vec<_> = timings.iter().filter_map(|t| t.cycle).collect();
+
         MonthlyReport {
             month: self.month,
             total_score: self.total_score / 1000,
             score: self.score_in_month / 1000,
-            tasks_done: self.tasks_done.iter().filter(|t| t.visible).count(),
+            tasks_done: self.tasks.count(StageId::Done),
             bugs_fixed: self.bugs_fixed_in_month,
             complexity: self.complexity,
+            outdated_dependencies: self
+                .dependencies
+                .iter()
+                .filter(|d| d.versions_behind() > 0)
+                .count(),
+            lead_time: average_days(timings.iter().map(|t| t.lead)),
+            cycle_time: average_days(cycle_times.into_iter()),
+            review_time: average_days(timings.iter().map(|t| t.review)),
+            team: self
+                .humans
+                .team()
+                .chain(
+                    self.humans
+                        .alumni()
+                        .filter(|h| h.stats != HumanStats::default()),
+                )
+                .map(|h| (h.display_name().to_string(), h.stats))
+                .collect(),
         }
     }
 
@@ -903,6 +1642,7 @@ impl WorldState {
             score,
             difficulty,
             max_time,
+            migrates,
         } = task;
         let task = if let Some(max_time) = max_time {
             let deadline = self.time + max_time;
@@ -910,7 +1650,13 @@ impl WorldState {
         } else {
             GameTask::new(id, created, description, kind, score, difficulty)
         };
-        self.tasks_backlog.push(task);
+        let task = GameTask { migrates, ..task };
+        self.tasks.push(task);
+        self.log(
+            LogCategory::Task,
+            "log.task_added",
+            &[("$TASK", task_label(id))],
+        );
         id
     }
 
@@ -920,30 +1666,39 @@ impl WorldState {
         }
     }
 
+    /// All tasks done so far, as if the whole game was a single month.
+    fn lifetime_done(&self) -> ArchivedMonth {
+        let mut total = archive::lifetime(&self.archive);
+        for task in self.tasks.in_stage(StageId::Done) {
+            total.add(task, self.time);
+        }
+        total
+    }
+
+    /// The number of tasks done since the beginning of the game.
+    pub fn tasks_done_in_total(&self) -> usize {
+        let archived: u32 = self.archive.iter().map(ArchivedMonth::tasks).sum();
+        archived as usize + self.tasks.count(StageId::Done)
+    }
+
     pub fn end_report(&self) -> FullReport {
+        let lifetime = self.lifetime_done();
         FullReport {
             product_name: self.product_name.clone(),
             months: self.month,
-            team_size: self.humans.iter().filter(|h| !h.quit).count() as u32,
+            team_size: self.humans.team_size() as u32,
             total_score: self.total_score / 1000,
-            tasks_done: self.tasks_done.len(),
+            tasks_done: lifetime.tasks() as usize,
             bugs_fixed: self.bugs_fixed_in_total,
+            lead_time: lifetime.lead_days(),
+            cycle_time: lifetime.cycle_days(),
         }
     }
 }
 
-#[derive(Debug)]
-pub enum TutorialEvent {}
-
-/// Save the whole state of the game onto local storage.
-pub fn save_to_storage(world_state: &WorldState) -> Result<(), JsValue> {
-    let local_storage = try_local_storage()?;
-
-    let data = serde_json::to_string(world_state).map_err(|e| JsValue::from(e.to_string()))?;
-
-    local_storage.set_item("save", &data)?;
-
-    Ok(())
+/// The label of a task as shown on the board.
+fn task_label(id: u32) -> String {
+    format!("T{}", id)
 }
 
 /// Gracefully try to obtain the Web local storage API.
@@ -973,32 +1728,31 @@ fn dummy_state() -> WorldState {
         complexity: 30,
         task_ingest_rate: 20,
         score_linger_rate: 0, // not relevant, will be recalculated
-        tasks_backlog: vec![
+        tasks: TaskBoard::from(vec![
             GameTask::new(1, 6_000, "Test tasks in general", TaskKind::Normal, 6, 20),
             GameTask::new(2, 6_000, "Test bugs in general", TaskKind::Bug, 2, 12),
             GameTask::new(3, 6_000, "Test chores in general", TaskKind::Chore, 0, 10),
-        ],
-        tasks_candidate: vec![],
-        tasks_progress: vec![GameTask {
-            id: 4,
-            created: 5_200,
-            deadline: None,
-            description: "Test a task in progress".to_string(),
-            kind: TaskKind::Normal,
-            stage: StageId::Progress,
-            assigned: None,
-            developed_by: None,
-            score: 7,
-            difficulty: 10,
-            progress: 0.25,
-            specified: true,
-            bugs: 1,
-            bugs_found: 0,
-            visible: true,
-        }],
-        tasks_review: vec![],
-        tasks_done: vec![],
-        humans: vec![
+            GameTask {
+                id: 4,
+                created: 5_200,
+                deadline: None,
+                description: "Test a task in progress".to_string(),
+                kind: TaskKind::Normal,
+                stage: StageId::Progress,
+                assigned: None,
+                developed_by: None,
+                score: 7,
+                difficulty: 10,
+                progress: 0.25,
+                specified: true,
+                bugs: 1,
+                bugs_found: 0,
+                migrates: None,
+                transitions: Vec::new(),
+            },
+        ]),
+        archive: vec![],
+        humans: Roster::from(vec![
             GameHuman::new(0, "You", "#fff", 100),
             GameHuman {
                 id: 1,
@@ -1007,10 +1761,320 @@ fn dummy_state() -> WorldState {
                 status: crate::components::human::HumanStatus::Idle,
                 experience: 100,
                 progress: 0.,
-                quit: false,
+                contract_until: None,
+                stats: Default::default(),
             },
-        ],
+        ]),
+        dependencies: initial_dependencies(),
         tutorial: None,
+        tutorial_since: 0,
         ceo_message_delivered: false,
+        log: vec![],
+        samples: vec![],
+        history: History::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::changes::StatePatch;
+
+    /// A state with task 4 under review by Guy, developed by You.
+    fn state_in_review() -> WorldState {
+        let mut state = dummy_state();
+        let task = state.tasks.move_to(4, StageId::Review).unwrap();
+        task.assigned = Some(1);
+        task.developed_by = Some(0);
+        task.progress = 1.;
+        state
+    }
+
+    fn reviews_done(state: &WorldState) -> u32 {
+        state.humans.member(1).unwrap().stats.reviews_done
+    }
+
+    #[test]
+    fn undo_and_redo_of_a_review_keep_stats_in_line() {
+        let mut state = state_in_review();
+        let mut reactor = EventReactor::new();
+        let task = TaskTransfer {
+            id: 4,
+            kind: TaskKind::Normal,
+            from: StageId::Review,
+            progress: 1.,
+        };
+
+        state
+            .apply_event(
+                EventBusRequest::MoveTask {
+                    task,
+                    to: StageId::Progress,
+                },
+                &mut reactor,
+            )
+            .unwrap();
+        assert_eq!(reviews_done(&state), 1);
+
+        for _ in 0..3 {
+            state
+                .apply_event(EventBusRequest::Undo, &mut reactor)
+                .unwrap();
+            assert_eq!(state.tasks.get(4).unwrap().stage, StageId::Review);
+            assert_eq!(reviews_done(&state), 0);
+
+            state
+                .apply_event(EventBusRequest::Redo, &mut reactor)
+                .unwrap();
+            assert_eq!(state.tasks.get(4).unwrap().stage, StageId::Progress);
+            assert_eq!(reviews_done(&state), 1);
+        }
+    }
+
+    #[test]
+    fn undo_of_a_stale_move_restores_the_stage_on_the_board() {
+        let mut state = state_in_review();
+        let mut reactor = EventReactor::new();
+        // the transfer claims the task is still a candidate
+        let task = TaskTransfer {
+            id: 4,
+            kind: TaskKind::Normal,
+            from: StageId::Candidate,
+            progress: 1.,
+        };
+
+        state
+            .apply_event(
+                EventBusRequest::MoveTask {
+                    task,
+                    to: StageId::Progress,
+                },
+                &mut reactor,
+            )
+            .unwrap();
+        state
+            .apply_event(EventBusRequest::Undo, &mut reactor)
+            .unwrap();
+        assert_eq!(state.tasks.get(4).unwrap().stage, StageId::Review);
+    }
+
+    #[test]
+    fn a_tick_reports_only_the_tasks_it_changed() {
+        let mut state = state_in_review();
+        let mut reactor = EventReactor::new();
+        state.tasks.move_to(2, StageId::Done);
+        state.tasks.take_changed();
+
+        let (_, changes) = state
+            .apply_event(EventBusRequest::Tick, &mut reactor)
+            .unwrap();
+        // tasks left alone in the backlog and done are not reported
+        assert!(!changes.tasks.contains(&1));
+        assert!(!changes.tasks.contains(&2));
+        assert!(changes.counters);
+        assert!(!changes.team);
+        assert!(changes.humans.contains(&1));
+    }
+
+    #[test]
+    fn patches_keep_a_copy_of_the_state_in_step() {
+        let mut state = state_in_review();
+        let mut copy = state.clone();
+        let mut reactor = EventReactor::new();
+
+        let mut requests = vec![EventBusRequest::MoveTask {
+            task: TaskTransfer {
+                id: 4,
+                kind: TaskKind::Normal,
+                from: StageId::Review,
+                progress: 1.,
+            },
+            to: StageId::Done,
+        }];
+        requests.extend((0..TICKS_PER_MONTH).map(|_| EventBusRequest::Tick));
+        requests.push(EventBusRequest::NextMonth);
+        requests.extend((0..10).map(|_| EventBusRequest::Tick));
+
+        for request in requests {
+            let (_, changes) = state.apply_event(request, &mut reactor).unwrap();
+            StatePatch::of(&state, &changes).apply_to(&mut copy);
+        }
+        assert_eq!(state.month, 7);
+        assert_eq!(
+            serde_json::to_value(&copy).unwrap(),
+            serde_json::to_value(&state).unwrap()
+        );
+    }
+
+    #[test]
+    fn humans_laid_off_cannot_be_assigned_until_rehired() {
+        let mut state = state_in_review();
+        let mut reactor = EventReactor::new();
+        let task = TaskTransfer {
+            id: 1,
+            kind: TaskKind::Normal,
+            from: StageId::Backlog,
+            progress: 0.,
+        };
+
+        state
+            .apply_event(EventBusRequest::LayOff { human_id: 1 }, &mut reactor)
+            .unwrap();
+        assert!(!state.humans.is_member(1));
+        // the review Guy was doing is left to someone else
+        assert_eq!(state.tasks.get(4).unwrap().assigned, None);
+
+        let assign = EventBusRequest::AssignTask {
+            task: task.clone(),
+            human_id: 1,
+        };
+        assert_eq!(
+            state
+                .apply_event(assign.clone(), &mut reactor)
+                .unwrap_err()
+                .0,
+            GameError::UnknownHuman(1)
+        );
+        assert_eq!(
+            state
+                .apply_event(EventBusRequest::LayOff { human_id: 1 }, &mut reactor)
+                .unwrap_err()
+                .0,
+            GameError::UnknownHuman(1)
+        );
+
+        state
+            .apply_event(EventBusRequest::Rehire { human_id: 1 }, &mut reactor)
+            .unwrap();
+        assert!(state.humans.is_member(1));
+        state.apply_event(assign, &mut reactor).unwrap();
+        assert_eq!(state.tasks.get(1).unwrap().assigned, Some(1));
+
+        assert_eq!(
+            state
+                .apply_event(EventBusRequest::Rehire { human_id: 1 }, &mut reactor)
+                .unwrap_err()
+                .0,
+            GameError::UnknownAlumnus(1)
+        );
+    }
+
+    #[test]
+    fn contractors_leave_when_their_month_is_over_and_return_for_good() {
+        let mut state = dummy_state();
+        let mut reactor = EventReactor::new();
+        let month = state.month;
+        state.humans.member_mut(1).unwrap().contract_until = Some(month);
+        state.time_in_month = TICKS_PER_MONTH;
+
+        state
+            .apply_event(EventBusRequest::NextMonth, &mut reactor)
+            .unwrap();
+        assert!(!state.humans.is_member(1));
+        assert!(state.humans.alumni().any(|h| h.id == 1));
+
+        state
+            .apply_event(EventBusRequest::Rehire { human_id: 1 }, &mut reactor)
+            .unwrap();
+        assert_eq!(state.humans.member(1).unwrap().contract_until, None);
+    }
+
+    #[test]
+    fn you_cannot_be_laid_off() {
+        let mut state = dummy_state();
+        let mut reactor = EventReactor::new();
+
+        let (outcome, _) = state
+            .apply_event(EventBusRequest::LayOff { human_id: 0 }, &mut reactor)
+            .unwrap();
+        assert!(matches!(outcome, EventOutcome::Alert(_)));
+        assert!(state.humans.is_member(0));
+    }
+
+    #[test]
+    fn rejected_moves_keep_undone_actions_for_redo() {
+        let mut state = state_in_review();
+        let mut reactor = EventReactor::new();
+        let task = TaskTransfer {
+            id: 4,
+            kind: TaskKind::Normal,
+            from: StageId::Review,
+            progress: 1.,
+        };
+
+        state
+            .apply_event(
+                EventBusRequest::MoveTask {
+                    task,
+                    to: StageId::Progress,
+                },
+                &mut reactor,
+            )
+            .unwrap();
+        state
+            .apply_event(EventBusRequest::Undo, &mut reactor)
+            .unwrap();
+
+        // a backlog task cannot be merged right away
+        let rejected = EventBusRequest::MoveTask {
+            task: TaskTransfer {
+                id: 1,
+                kind: TaskKind::Normal,
+                from: StageId::Backlog,
+                progress: 0.,
+            },
+            to: StageId::Done,
+        };
+        assert_eq!(
+            state.apply_event(rejected, &mut reactor).unwrap_err().0,
+            GameError::Move(MoveError::Invalid)
+        );
+
+        state
+            .apply_event(EventBusRequest::Redo, &mut reactor)
+            .unwrap();
+        assert_eq!(state.tasks.get(4).unwrap().stage, StageId::Progress);
+    }
+
+    #[test]
+    fn rejected_redos_can_be_tried_again() {
+        let mut state = dummy_state();
+        let mut reactor = EventReactor::new();
+        let task = TaskTransfer {
+            id: 1,
+            kind: TaskKind::Normal,
+            from: StageId::Backlog,
+            progress: 0.,
+        };
+
+        state
+            .apply_event(
+                EventBusRequest::AssignTask { task, human_id: 1 },
+                &mut reactor,
+            )
+            .unwrap();
+        state
+            .apply_event(EventBusRequest::Undo, &mut reactor)
+            .unwrap();
+        state
+            .apply_event(EventBusRequest::LayOff { human_id: 1 }, &mut reactor)
+            .unwrap();
+
+        // nobody to assign the task to, and nothing changed for it
+        assert_eq!(
+            state
+                .apply_event(EventBusRequest::Redo, &mut reactor)
+                .unwrap_err(),
+            (GameError::UnknownHuman(1), ChangeSet::default())
+        );
+        assert_eq!(state.tasks.get(1).unwrap().assigned, None);
+
+        state
+            .apply_event(EventBusRequest::Rehire { human_id: 1 }, &mut reactor)
+            .unwrap();
+        state
+            .apply_event(EventBusRequest::Redo, &mut reactor)
+            .unwrap();
+        assert_eq!(state.tasks.get(1).unwrap().assigned, Some(1));
     }
 }
@@ -0,0 +1,307 @@
+//! Module for the storage of the tasks on the board.
+//!
+//! All tasks are kept in a single map by ID,
+//! and the `stage` of each task says where it is.
+//! Each stage keeps the IDs of its tasks in the order shown on the board,
+//! so that going through a stage does not go through the others
+//! (the tasks done in particular only ever grow).
+//! The board also records which tasks were changed through it,
+//! so that the changes of a request are known without comparing boards.
+//!
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::components::{stage::StageId, task::GameTask};
+
+/// The stages of the tasks which were not merged yet.
+const OPEN_STAGES: [StageId; 4] = [
+    StageId::Backlog,
+    StageId::Candidate,
+    StageId::Progress,
+    StageId::Review,
+];
+
+/// The position of the stage's task list in the board.
+fn slot(stage: StageId) -> usize {
+    match stage {
+        StageId::Backlog => 0,
+        StageId::Candidate => 1,
+        StageId::Progress => 2,
+        StageId::Review => 3,
+        StageId::Done => 4,
+    }
+}
+
+/// All tasks on the board.
+///
+/// It is saved as a list of the tasks,
+/// stage by stage and from top to bottom.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(from = "Vec<GameTask>")]
+pub struct TaskBoard {
+    /// the tasks by ID
+    tasks: HashMap<u32, GameTask>,
+    /// the IDs of the tasks in each stage, from top to bottom
+    stages: [Vec<u32>; 5],
+    /// the IDs of the tasks added, changed or removed
+    /// since they were last taken
+    changed: BTreeSet<u32>,
+}
+
+impl PartialEq for TaskBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.tasks == other.tasks && self.stages == other.stages
+    }
+}
+
+impl TaskBoard {
+    /// Get the task of the given ID, wherever it is.
+    pub fn get(&self, id: u32) -> Option<&GameTask> {
+        self.tasks.get(&id)
+    }
+
+    /// Get the task of the given ID for changing it.
+    ///
+    /// Its stage must be changed through [`TaskBoard::move_to`].
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut GameTask> {
+        let task = self.tasks.get_mut(&id)?;
+        self.changed.insert(id);
+        Some(task)
+    }
+
+    /// The number of tasks in the given stage.
+    pub fn count(&self, stage: StageId) -> usize {
+        self.stages[slot(stage)].len()
+    }
+
+    /// The tasks in the given stage, from top to bottom.
+    pub fn in_stage(&self, stage: StageId) -> impl Iterator<Item = &GameTask> {
+        self.stages[slot(stage)]
+            .iter()
+            .filter_map(move |id| self.tasks.get(id))
+    }
+
+    /// The tasks which were not merged yet.
+    pub fn open(&self) -> impl Iterator<Item = &GameTask> {
+        OPEN_STAGES
+            .iter()
+            .flat_map(move |stage| self.in_stage(*stage))
+    }
+
+    /// All tasks, stage by stage.
+    pub fn iter(&self) -> impl Iterator<Item = &GameTask> {
+        self.open().chain(self.in_stage(StageId::Done))
+    }
+
+    /// Change each task in the given stage, from top to bottom.
+    ///
+    /// The function returns whether it changed the task.
+    pub fn for_each_mut(&mut self, stage: StageId, mut f: impl FnMut(&mut GameTask) -> bool) {
+        for id in &self.stages[slot(stage)] {
+            if let Some(task) = self.tasks.get_mut(id) {
+                if f(task) {
+                    self.changed.insert(*id);
+                }
+            }
+        }
+    }
+
+    /// Change each task which was not merged yet.
+    ///
+    /// The function returns whether it changed the task.
+    pub fn for_each_open_mut(&mut self, mut f: impl FnMut(&mut GameTask) -> bool) {
+        for stage in OPEN_STAGES {
+            self.for_each_mut(stage, &mut f);
+        }
+    }
+
+    /// Place a task at the bottom of its stage.
+    ///
+    /// A task with the same ID is replaced.
+    pub fn push(&mut self, task: GameTask) {
+        if let Some(old) = self.tasks.get(&task.id) {
+            let old_stage = old.stage;
+            self.stages[slot(old_stage)].retain(|id| *id != task.id);
+        }
+        self.stages[slot(task.stage)].push(task.id);
+        self.changed.insert(task.id);
+        self.tasks.insert(task.id, task);
+    }
+
+    /// Replace a task with a newer copy of it,
+    /// keeping its place on the board unless it moved to another stage.
+    ///
+    /// A task which was not on the board is placed at the bottom of its stage.
+    pub fn put(&mut self, task: GameTask) {
+        match self.tasks.get(&task.id) {
+            Some(old) if old.stage == task.stage => {
+                self.changed.insert(task.id);
+                self.tasks.insert(task.id, task);
+            }
+            _ => self.push(task),
+        }
+    }
+
+    /// Remove the task of the given ID, wherever it is.
+    pub fn remove(&mut self, id: u32) -> Option<GameTask> {
+        let task = self.tasks.remove(&id)?;
+        self.stages[slot(task.stage)].retain(|t| *t != id);
+        self.changed.insert(id);
+        Some(task)
+    }
+
+    /// Remove all tasks in the given stage, from top to bottom.
+    pub fn take_stage(&mut self, stage: StageId) -> Vec<GameTask> {
+        let ids = std::mem::take(&mut self.stages[slot(stage)]);
+        self.changed.extend(&ids);
+        ids.into_iter()
+            .filter_map(|id| self.tasks.remove(&id))
+            .collect()
+    }
+
+    /// Take the IDs of the tasks added, changed or removed
+    /// since this was last called.
+    pub fn take_changed(&mut self) -> BTreeSet<u32> {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Move a task to the bottom of another stage.
+    ///
+    /// Returns the task moved,
+    /// or `None` if there is no task with this ID.
+    pub fn move_to(&mut self, id: u32, to: StageId) -> Option<&mut GameTask> {
+        let task = self.tasks.get_mut(&id)?;
+        if task.stage != to {
+            self.stages[slot(task.stage)].retain(|t| *t != id);
+            self.stages[slot(to)].push(id);
+            task.stage = to;
+        }
+        self.changed.insert(id);
+        Some(task)
+    }
+}
+
+impl From<Vec<GameTask>> for TaskBoard {
+    fn from(tasks: Vec<GameTask>) -> Self {
+        let mut board = TaskBoard::default();
+        for task in tasks {
+            board.push(task);
+        }
+        // a board read as a whole starts with nothing changed
+        board.changed.clear();
+        board
+    }
+}
+
+impl Serialize for TaskBoard {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::task::TaskKind;
+
+    fn task(id: u32, stage: StageId) -> GameTask {
+        GameTask {
+            stage,
+            ..GameTask::new(id, 0, "task.feature", TaskKind::Normal, 4, 10)
+        }
+    }
+
+    fn ids(board: &TaskBoard, stage: StageId) -> Vec<u32> {
+        board.in_stage(stage).map(|t| t.id).collect()
+    }
+
+    fn backlog_of(ids: &[u32]) -> TaskBoard {
+        TaskBoard::from(
+            ids.iter()
+                .map(|id| task(*id, StageId::Backlog))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn moves_keep_stages_and_tasks_in_step() {
+        let mut board = backlog_of(&[1, 2, 3]);
+
+        board.move_to(2, StageId::Candidate).unwrap();
+        board.move_to(1, StageId::Candidate).unwrap();
+        assert_eq!(ids(&board, StageId::Backlog), [3]);
+        assert_eq!(ids(&board, StageId::Candidate), [2, 1]);
+        assert_eq!(board.get(1).unwrap().stage, StageId::Candidate);
+        assert_eq!(board.get(2).unwrap().stage, StageId::Candidate);
+
+        // moving into the same stage keeps the order
+        board.move_to(2, StageId::Candidate).unwrap();
+        assert_eq!(ids(&board, StageId::Candidate), [2, 1]);
+
+        assert!(board.move_to(9, StageId::Candidate).is_none());
+        assert_eq!(board.count(StageId::Candidate), 2);
+        assert_eq!(board.iter().count(), 3);
+    }
+
+    #[test]
+    fn taking_a_stage_removes_its_tasks_in_order() {
+        let mut board = backlog_of(&[1, 2, 3, 4]);
+        board.move_to(3, StageId::Done).unwrap();
+        board.move_to(1, StageId::Done).unwrap();
+
+        let done: Vec<_> = board
+            .take_stage(StageId::Done)
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(done, [3, 1]);
+        assert_eq!(board.count(StageId::Done), 0);
+        assert!(board.get(1).is_none());
+        assert!(board.get(3).is_none());
+        assert_eq!(ids(&board, StageId::Backlog), [2, 4]);
+    }
+
+    #[test]
+    fn boards_read_from_a_list_rebuild_the_stages_in_order() {
+        let board: TaskBoard = serde_json::from_value(serde_json::json!([
+            task(5, StageId::Done),
+            task(3, StageId::Backlog),
+            task(4, StageId::Review),
+            task(1, StageId::Backlog),
+        ]))
+        .unwrap();
+
+        assert_eq!(ids(&board, StageId::Backlog), [3, 1]);
+        assert_eq!(ids(&board, StageId::Review), [4]);
+        assert_eq!(ids(&board, StageId::Done), [5]);
+        assert_eq!(board.get(4).unwrap().stage, StageId::Review);
+
+        // saved stage by stage, and read back the same
+        let saved = serde_json::to_value(&board).unwrap();
+        let ids_saved: Vec<_> = saved
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_u64().unwrap())
+            .collect();
+        assert_eq!(ids_saved, [3, 1, 4, 5]);
+        assert_eq!(serde_json::from_value::<TaskBoard>(saved).unwrap(), board);
+    }
+
+    #[test]
+    fn changes_are_recorded_where_tasks_change() {
+        let mut board = backlog_of(&[1, 2, 3]);
+        assert!(board.take_changed().is_empty());
+
+        board.move_to(2, StageId::Candidate);
+        board.get_mut(3).unwrap().progress = 0.5;
+        board.for_each_mut(StageId::Backlog, |t| t.id == 1);
+        assert_eq!(board.take_changed().into_iter().collect::<Vec<_>>(), [1, 2, 3]);
+        assert!(board.take_changed().is_empty());
+    }
+}
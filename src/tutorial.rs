@@ -0,0 +1,254 @@
+//! Module for the onboarding tutorial.
+//!
+//! The tutorial is a script of steps.
+//! Each step is entered once its trigger happens in the game,
+//! applies its side effects to the world state,
+//! and shows its tutorial message.
+//!
+
+use crate::components::{stage::StageId, task::TaskKind};
+
+/// Something that happened in the game
+/// which may advance the tutorial.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TutorialEvent {
+    /// the player acknowledged the current tutorial message
+    Acknowledged,
+    /// some in-game time passed
+    Tick,
+    /// the task with this ID was fully specified
+    TaskSpecified(u32),
+    /// the task with this ID was fully developed
+    TaskDeveloped(u32),
+    /// a bug was found while reviewing the task with this ID
+    BugFound(u32),
+    /// the task with this ID was moved to a stage other than Done
+    TaskMoved(u32, StageId),
+    /// the task with this ID was merged upstream
+    TaskMerged(u32),
+    /// the current month is about to end
+    NearEndOfMonth,
+}
+
+impl TutorialEvent {
+    /// The ID of the task involved in the event, if any.
+    pub fn task_id(self) -> Option<u32> {
+        match self {
+            TutorialEvent::TaskSpecified(id)
+            | TutorialEvent::TaskDeveloped(id)
+            | TutorialEvent::BugFound(id)
+            | TutorialEvent::TaskMoved(id, _)
+            | TutorialEvent::TaskMerged(id) => Some(id),
+            TutorialEvent::Acknowledged | TutorialEvent::Tick | TutorialEvent::NearEndOfMonth => {
+                None
+            }
+        }
+    }
+}
+
+/// The condition for entering a tutorial step.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TutorialTrigger {
+    /// the player pressed OK on the previous message
+    Acknowledged,
+    /// this many ticks passed since the previous step
+    AfterTicks(u32),
+    /// a task was fully specified
+    TaskSpecified,
+    /// a task was fully developed
+    TaskDeveloped,
+    /// a bug was found through review
+    BugFound,
+    /// a task reached the given stage (other than Done)
+    TaskReachedStage(StageId),
+    /// a task was merged upstream
+    TaskMerged,
+    /// the month is about to end
+    NearEndOfMonth,
+}
+
+impl TutorialTrigger {
+    /// Whether the event fulfills this trigger,
+    /// given the number of ticks since the current step was entered.
+    pub fn is_met(self, event: TutorialEvent, elapsed: u32) -> bool {
+        match (self, event) {
+            (TutorialTrigger::Acknowledged, TutorialEvent::Acknowledged) => true,
+            (TutorialTrigger::AfterTicks(ticks), TutorialEvent::Tick) => elapsed >= ticks,
+            (TutorialTrigger::TaskSpecified, TutorialEvent::TaskSpecified(_)) => true,
+            (TutorialTrigger::TaskDeveloped, TutorialEvent::TaskDeveloped(_)) => true,
+            (TutorialTrigger::BugFound, TutorialEvent::BugFound(_)) => true,
+            (TutorialTrigger::TaskReachedStage(stage), TutorialEvent::TaskMoved(_, to)) => {
+                stage == to
+            }
+            (TutorialTrigger::TaskMerged, TutorialEvent::TaskMerged(_)) => true,
+            (TutorialTrigger::NearEndOfMonth, TutorialEvent::NearEndOfMonth) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A side effect of entering a tutorial step.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TutorialEffect {
+    /// add a task to the backlog
+    /// (the description is a message ID)
+    AddTask {
+        description: &'static str,
+        kind: TaskKind,
+        score: i32,
+        difficulty: u32,
+    },
+    /// add a human to the team
+    AddHuman {
+        name: &'static str,
+        color: &'static str,
+        experience: u32,
+    },
+    /// ensure that the task involved in the trigger has a bug
+    PlantBug,
+    /// give experience to a human
+    AddExperience { human_id: u32, amount: u32 },
+}
+
+/// A step of the tutorial.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TutorialStep {
+    /// the tutorial message to show, if any
+    pub message: Option<u32>,
+    /// the condition to enter this step
+    pub trigger: TutorialTrigger,
+    /// the earliest step from which this step can be entered directly,
+    /// skipping the steps in between
+    pub skip_from: Option<u32>,
+    /// what happens in the game when entering this step
+    pub effects: &'static [TutorialEffect],
+    /// whether You can devise new tasks during this step
+    pub ingestion: bool,
+}
+
+impl TutorialStep {
+    const fn new(message: u32, trigger: TutorialTrigger) -> Self {
+        TutorialStep {
+            message: Some(message),
+            trigger,
+            skip_from: None,
+            effects: &[],
+            ingestion: false,
+        }
+    }
+
+    const fn with_effects(mut self, effects: &'static [TutorialEffect]) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    const fn skippable_from(mut self, step: u32) -> Self {
+        self.skip_from = Some(step);
+        self
+    }
+
+    const fn with_ingestion(mut self) -> Self {
+        self.ingestion = true;
+        self
+    }
+}
+
+/// The onboarding month script.
+pub static ONBOARDING: [TutorialStep; 14] = [
+    // waiting for the game to start
+    TutorialStep {
+        message: None,
+        trigger: TutorialTrigger::Acknowledged,
+        skip_from: None,
+        effects: &[],
+        ingestion: false,
+    },
+    // introduction
+    TutorialStep::new(1, TutorialTrigger::AfterTicks(4)),
+    // the workboard
+    TutorialStep::new(2, TutorialTrigger::Acknowledged),
+    // a first task is coming
+    TutorialStep::new(3, TutorialTrigger::Acknowledged),
+    // specify the task
+    TutorialStep::new(4, TutorialTrigger::AfterTicks(7)).with_effects(&[TutorialEffect::AddTask {
+        description: "tutorial.task.easy",
+        kind: TaskKind::Normal,
+        score: 5,
+        difficulty: 2,
+    }]),
+    // assign and develop the task
+    TutorialStep::new(5, TutorialTrigger::TaskSpecified),
+    // review the task
+    TutorialStep::new(6, TutorialTrigger::TaskDeveloped).with_effects(&[
+        TutorialEffect::PlantBug,
+        // make the bug a bit easier to find
+        TutorialEffect::AddExperience {
+            human_id: 0,
+            amount: 2,
+        },
+    ]),
+    // a bug was found
+    TutorialStep::new(7, TutorialTrigger::BugFound),
+    // review again
+    TutorialStep::new(8, TutorialTrigger::TaskReachedStage(StageId::Review)),
+    // the task was merged
+    TutorialStep::new(9, TutorialTrigger::TaskMerged).skippable_from(6),
+    // a bug task appears
+    TutorialStep::new(10, TutorialTrigger::AfterTicks(17)).with_effects(&[
+        TutorialEffect::AddTask {
+            description: "tutorial.task.bug",
+            kind: TaskKind::Bug,
+            score: 2,
+            difficulty: 4,
+        },
+    ]),
+    // Guy joins the team
+    TutorialStep::new(11, TutorialTrigger::TaskSpecified).with_effects(&[
+        TutorialEffect::AddHuman {
+            name: "Guy",
+            color: "#333",
+            experience: 126,
+        },
+    ]),
+    // a chore task appears
+    TutorialStep::new(12, TutorialTrigger::AfterTicks(105))
+        .with_effects(&[TutorialEffect::AddTask {
+            description: "tutorial.task.chore",
+            kind: TaskKind::Chore,
+            score: 0,
+            difficulty: 3,
+        }])
+        .with_ingestion(),
+    // final tips
+    TutorialStep::new(13, TutorialTrigger::NearEndOfMonth)
+        .skippable_from(0)
+        .with_ingestion(),
+];
+
+/// Obtain a step of the tutorial script.
+pub fn step(index: u32) -> Option<&'static TutorialStep> {
+    ONBOARDING.get(index as usize)
+}
+
+/// Find the next step to enter after the given event,
+/// if the event triggers one.
+pub fn next_step(current: u32, event: TutorialEvent, elapsed: u32) -> Option<u32> {
+    ONBOARDING
+        .iter()
+        .enumerate()
+        .skip(current as usize + 1)
+        .find(|(i, step)| {
+            let reachable = *i == current as usize + 1
+                || step.skip_from.map(|from| current >= from).unwrap_or(false);
+            reachable && step.trigger.is_met(event, elapsed)
+        })
+        .map(|(i, _)| i as u32)
+}
+
+/// Whether the player's acknowledgement of the current step's message
+/// should advance the tutorial.
+pub fn advances_on_acknowledge(current: u32) -> bool {
+    step(current + 1)
+        .map(|s| s.trigger == TutorialTrigger::Acknowledged)
+        .unwrap_or(false)
+}